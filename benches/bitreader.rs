@@ -12,5 +12,36 @@ pub fn bitreader_is_empty_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bitreader_is_empty_benchmark);
+/// Read 1-bit flags interleaved with small fields, as meta-block header parsing does.
+pub fn bitreader_flag_reads_benchmark(c: &mut Criterion) {
+    let data: Vec<u8> = (0..1024u32).map(|i| (i * 37) as u8).collect();
+
+    c.bench_function("BitReader::read_bits() flags", |b| {
+        b.iter(|| {
+            let mut reader = BitReader::new(black_box(&data));
+            let mut acc = 0u32;
+            while let (Ok(flag), Ok(field)) = (reader.read_bits(1), reader.read_bits(2)) {
+                acc = acc.wrapping_add(flag).wrapping_add(field);
+            }
+            black_box(acc);
+        });
+    });
+
+    c.bench_function("BitReader::read_const() flags", |b| {
+        b.iter(|| {
+            let mut reader = BitReader::new(black_box(&data));
+            let mut acc = 0u32;
+            while let (Ok(flag), Ok(field)) = (reader.read_const::<1>(), reader.read_const::<2>()) {
+                acc = acc.wrapping_add(flag).wrapping_add(field);
+            }
+            black_box(acc);
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    bitreader_is_empty_benchmark,
+    bitreader_flag_reads_benchmark
+);
 criterion_main!(benches);
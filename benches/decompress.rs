@@ -0,0 +1,199 @@
+use brotschneider::huffman::{MAX_CODE_LENGTH, lengths_from_frequencies};
+use brotschneider::metablock::{MetaBlockHeader, write_uncompressed_block};
+use brotschneider::{BitWriter, HuffmanTree, decompress};
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+
+/// The number of bytes each fixture decompresses to, roughly.
+const OUTPUT_LEN: usize = 1 << 20;
+
+/// The number of literals per meta-block of the literal fixtures.
+const LITERAL_BLOCK_LEN: usize = 1 << 16;
+
+/// Insert code 23 (insert length 22594 + 24 extra bits) with copy code 0.
+const LONG_INSERT_SYMBOL: u16 = 7 * 64 + 7 * 8;
+
+/// Insert code 1 (a single literal) with copy code 23 (copy length 2118 + 24 extra bits).
+const LONG_COPY_SYMBOL: u16 = 6 * 64 + 8 + 7;
+
+/// The number of bytes each command of the copy fixtures copies.
+const COPY_LEN: u32 = 4096;
+
+/// Pseudo-random numbers from a fixed seed, so every run decodes the same fixtures.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_u32(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as u32
+    }
+}
+
+/// English-like text built from a small vocabulary.
+fn text(len: usize) -> Vec<u8> {
+    const WORDS: [&str; 16] = [
+        "the",
+        "quick",
+        "brown",
+        "fox",
+        "jumps",
+        "over",
+        "lazy",
+        "dog",
+        "and",
+        "a",
+        "stream",
+        "of",
+        "compressed",
+        "bytes",
+        "window",
+        "decoder",
+    ];
+
+    let mut rng = Lcg(1);
+    let mut text = Vec::with_capacity(len + 16);
+    while text.len() < len {
+        text.extend_from_slice(WORDS[rng.next_u32() as usize % WORDS.len()].as_bytes());
+        text.push(if rng.next_u32().is_multiple_of(12) {
+            b'\n'
+        } else {
+            b' '
+        });
+    }
+    text.truncate(len);
+    text
+}
+
+/// Uniformly distributed bytes, which no prefix code can shorten.
+fn binary(len: usize) -> Vec<u8> {
+    let mut rng = Lcg(2);
+    (0..len).map(|_| rng.next_u32() as u8).collect()
+}
+
+/// Write the header of a compressed meta-block with a single block type and tree per
+/// category and NPOSTFIX = NDIRECT = 0.
+fn write_compressed_header(writer: &mut BitWriter, length: usize, is_last: bool) {
+    MetaBlockHeader {
+        is_last,
+        is_last_empty: false,
+        is_metadata: false,
+        is_uncompressed: false,
+        length: length as u32,
+    }
+    .write(writer)
+    .unwrap();
+    writer.write_bits(0, 13).unwrap();
+}
+
+/// A stream of literal-only meta-blocks, each inserting all of its bytes with a single
+/// command and a literal code built from the byte frequencies.
+fn literal_stream(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    writer.write_bit(false).unwrap(); // WBITS = 16
+
+    let blocks = data.chunks(LITERAL_BLOCK_LEN);
+    let count = blocks.len();
+    for (i, block) in blocks.enumerate() {
+        let mut frequencies = [0u32; 256];
+        for &byte in block {
+            frequencies[byte as usize] += 1;
+        }
+        let lengths = lengths_from_frequencies(&frequencies, MAX_CODE_LENGTH).unwrap();
+        let tree = HuffmanTree::from_code_lengths(&lengths).unwrap();
+
+        write_compressed_header(&mut writer, block.len(), i + 1 == count);
+        writer.write_complex_code(&lengths).unwrap();
+        writer
+            .write_simple_code(&[LONG_INSERT_SYMBOL], 704)
+            .unwrap();
+        writer.write_simple_code(&[0], 64).unwrap();
+
+        writer.write_bits(block.len() as u32 - 22594, 24).unwrap();
+        for &byte in block {
+            let (code, len) = tree.code_for(byte as u16).unwrap();
+            writer.write_bits(code, len).unwrap();
+        }
+    }
+
+    writer.into_inner()
+}
+
+/// Returns the distance code, extra bits and number of extra bits of `distance`, with
+/// NPOSTFIX = NDIRECT = 0.
+fn distance_code(distance: u32) -> (u16, u32, u8) {
+    let x = distance + 3;
+    let nbits = (31 - x.leading_zeros()) as u8 - 1;
+    let high = (x >> nbits) & 1;
+    let code = 16 + 2 * (nbits as u16 - 1) + high as u16;
+
+    (code, x & ((1 << nbits) - 1), nbits)
+}
+
+/// A stream of 64 KiB of text followed by commands that each insert one literal and copy
+/// `COPY_LEN` bytes from a distance drawn from `distances`.
+///
+/// All distances must share one distance code, so that the distance prefix code has a
+/// single symbol.
+fn copy_stream(distances: impl Fn(&mut Lcg) -> u32) -> Vec<u8> {
+    const COMMANDS_PER_BLOCK: usize = 64;
+
+    let mut writer = BitWriter::new();
+    writer.write_bit(false).unwrap(); // WBITS = 16
+    write_uncompressed_block(&mut writer, &text(LITERAL_BLOCK_LEN), false).unwrap();
+
+    let mut rng = Lcg(3);
+    let command_len = 1 + COPY_LEN as usize;
+    let blocks = (OUTPUT_LEN - LITERAL_BLOCK_LEN) / (COMMANDS_PER_BLOCK * command_len);
+    for i in 0..blocks {
+        let commands: Vec<_> = (0..COMMANDS_PER_BLOCK)
+            .map(|_| distance_code(distances(&mut rng)))
+            .collect();
+
+        write_compressed_header(
+            &mut writer,
+            COMMANDS_PER_BLOCK * command_len,
+            i + 1 == blocks,
+        );
+        writer.write_simple_code(&[b'.' as u16], 256).unwrap();
+        writer.write_simple_code(&[LONG_COPY_SYMBOL], 704).unwrap();
+        let distance_symbol = commands[0].0;
+        writer.write_simple_code(&[distance_symbol], 64).unwrap();
+
+        for (code, extra, nbits) in commands {
+            assert_eq!(code, distance_symbol);
+            writer.write_bits(COPY_LEN - 2118, 24).unwrap();
+            writer.write_bits(extra, nbits).unwrap();
+        }
+    }
+
+    writer.into_inner()
+}
+
+pub fn decompress_throughput_benchmark(c: &mut Criterion) {
+    let fixtures = [
+        ("text", literal_stream(&text(OUTPUT_LEN))),
+        ("binary", literal_stream(&binary(OUTPUT_LEN))),
+        // Overlapping copies at distance 1, which repeat a single byte.
+        ("runs", copy_stream(|_| 1)),
+        // Copies from 32 to 48 KiB back, far from the most recent output.
+        (
+            "far copies",
+            copy_stream(|rng| 32765 + rng.next_u32() % (1 << 14)),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("decompress()");
+    for (name, stream) in &fixtures {
+        let output_len = decompress(stream).unwrap().len();
+        group.throughput(Throughput::Bytes(output_len as u64));
+        group.bench_function(*name, |b| {
+            b.iter(|| black_box(decompress(black_box(stream)).unwrap()));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decompress_throughput_benchmark);
+criterion_main!(benches);
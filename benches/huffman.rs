@@ -0,0 +1,169 @@
+use brotschneider::bitreader::BitReader;
+use brotschneider::huffman::{HuffmanScratch, HuffmanTree};
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+/// 256 symbols, all with an 8-bit code.
+fn uniform_lengths() -> Vec<u8> {
+    vec![8; 256]
+}
+
+/// Lengths 1, 2, ..., 7, 8, 8 (a maximally skewed complete tree), padded with unused symbols.
+fn skewed_lengths() -> Vec<u8> {
+    let mut lengths: Vec<u8> = (1..=8).collect();
+    lengths.push(8);
+    lengths.resize(256, 0);
+    lengths
+}
+
+/// Lengths 1, 2, ..., 15, 15 (a complete tree using Brotli's maximum code length).
+fn deep_lengths() -> Vec<u8> {
+    let mut lengths: Vec<u8> = (1..=15).collect();
+    lengths.push(15);
+    lengths
+}
+
+pub fn huffman_from_code_lengths_benchmark(c: &mut Criterion) {
+    let tables = [
+        ("uniform", uniform_lengths()),
+        ("skewed", skewed_lengths()),
+        ("15-bit deep", deep_lengths()),
+    ];
+
+    for (name, lengths) in &tables {
+        c.bench_function(&format!("HuffmanTree::from_code_lengths() {name}"), |b| {
+            b.iter(|| {
+                black_box(HuffmanTree::from_code_lengths(black_box(lengths)).unwrap());
+            });
+        });
+    }
+}
+
+pub fn huffman_scratch_reuse_benchmark(c: &mut Criterion) {
+    let tables = [uniform_lengths(), skewed_lengths(), deep_lengths()];
+
+    c.bench_function("HuffmanTree::from_code_lengths() x1000", |b| {
+        b.iter(|| {
+            for lengths in tables.iter().cycle().take(1000) {
+                black_box(HuffmanTree::from_code_lengths(black_box(lengths)).unwrap());
+            }
+        });
+    });
+
+    c.bench_function("HuffmanTree::from_code_lengths_with_scratch() x1000", |b| {
+        let mut scratch = HuffmanScratch::new();
+        b.iter(|| {
+            for lengths in tables.iter().cycle().take(1000) {
+                black_box(
+                    HuffmanTree::from_code_lengths_with_scratch(black_box(lengths), &mut scratch)
+                        .unwrap(),
+                );
+            }
+        });
+    });
+}
+
+pub fn huffman_tiny_trees_benchmark(c: &mut Criterion) {
+    // Small codes like those of block-type, block-count and context-map alphabets.
+    let tables: [&[u8]; 4] = [&[1, 1], &[1, 2, 2], &[2, 2, 2, 2], &[1, 2, 3, 3]];
+
+    c.bench_function(
+        "HuffmanTree::from_code_lengths_with_scratch() tiny x1000",
+        |b| {
+            let mut scratch = HuffmanScratch::new();
+            b.iter(|| {
+                for lengths in tables.iter().cycle().take(1000) {
+                    black_box(
+                        HuffmanTree::from_code_lengths_with_scratch(
+                            black_box(lengths),
+                            &mut scratch,
+                        )
+                        .unwrap(),
+                    );
+                }
+            });
+        },
+    );
+
+    c.bench_function("HuffmanTree::from_code_lengths_into() tiny x1000", |b| {
+        let mut scratch = HuffmanScratch::new();
+        let mut tree = HuffmanTree::from_code_lengths(&[1, 1]).unwrap();
+        b.iter(|| {
+            for lengths in tables.iter().cycle().take(1000) {
+                HuffmanTree::from_code_lengths_into(black_box(lengths), &mut scratch, &mut tree)
+                    .unwrap();
+                black_box(&tree);
+            }
+        });
+    });
+}
+
+pub fn huffman_decode_symbol_benchmark(c: &mut Criterion) {
+    let tree = HuffmanTree::from_code_lengths(&uniform_lengths()).unwrap();
+    let data: Vec<u8> = (0..4096u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+        .collect();
+
+    c.bench_function("HuffmanTree::decode_symbol() uniform x4096", |b| {
+        b.iter(|| {
+            let mut reader = BitReader::new(black_box(&data));
+            for _ in 0..data.len() {
+                black_box(tree.decode_symbol(&mut reader).unwrap());
+            }
+        });
+    });
+}
+
+/// Decodes symbols with both `decode_symbol` and `decode_symbol_unchecked`.
+#[cfg(feature = "fast-decode")]
+pub fn huffman_decode_symbol_unchecked_benchmark(c: &mut Criterion) {
+    let data: Vec<u8> = (0..4096u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+        .collect();
+
+    for (name, lengths) in [("uniform", uniform_lengths()), ("skewed", skewed_lengths())] {
+        let tree = HuffmanTree::from_code_lengths(&lengths).unwrap();
+        tree.validate_complete().unwrap();
+        let max_bits = tree.max_bits as usize;
+
+        c.bench_function(&format!("HuffmanTree::decode_symbol() {name}"), |b| {
+            b.iter(|| {
+                let mut reader = BitReader::new(black_box(&data));
+                while reader.bits_remaining() >= max_bits {
+                    black_box(tree.decode_symbol(&mut reader).unwrap());
+                }
+            });
+        });
+
+        c.bench_function(
+            &format!("HuffmanTree::decode_symbol_unchecked() {name}"),
+            |b| {
+                b.iter(|| {
+                    let mut reader = BitReader::new(black_box(&data));
+                    while reader.bits_remaining() >= max_bits {
+                        // SAFETY: the tree is complete and unmodified, and `max_bits` bits remain.
+                        black_box(unsafe { tree.decode_symbol_unchecked(&mut reader) });
+                    }
+                });
+            },
+        );
+    }
+}
+
+#[cfg(not(feature = "fast-decode"))]
+criterion_group!(
+    benches,
+    huffman_from_code_lengths_benchmark,
+    huffman_scratch_reuse_benchmark,
+    huffman_tiny_trees_benchmark,
+    huffman_decode_symbol_benchmark
+);
+#[cfg(feature = "fast-decode")]
+criterion_group!(
+    benches,
+    huffman_from_code_lengths_benchmark,
+    huffman_scratch_reuse_benchmark,
+    huffman_tiny_trees_benchmark,
+    huffman_decode_symbol_benchmark,
+    huffman_decode_symbol_unchecked_benchmark
+);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+//! Decompress a Brotli file.
+//!
+//! Usage: `cargo run --example brotli_decode -- <input> [output]`
+//!
+//! The decompressed bytes are written to `output`, or to stdout if it is omitted. Decode
+//! statistics are printed to stderr.
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::time::Instant;
+use std::{env, fs};
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let (Some(input_path), output_path) = (args.next(), args.next()) else {
+        eprintln!("usage: brotli_decode <input> [output]");
+        return ExitCode::FAILURE;
+    };
+
+    let input = match fs::read(&input_path) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: cannot read {input_path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let start = Instant::now();
+    let output = match brotschneider::decompress(&input) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("error: cannot decompress {input_path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let elapsed = start.elapsed();
+
+    let written = match &output_path {
+        Some(path) => fs::write(path, &output),
+        None => io::stdout().lock().write_all(&output),
+    };
+    if let Err(e) = written {
+        eprintln!("error: cannot write output: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    let ratio = output.len() as f64 / input.len().max(1) as f64;
+    eprintln!(
+        "{} -> {} bytes (ratio {ratio:.2}) in {elapsed:.2?}",
+        input.len(),
+        output.len(),
+    );
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,122 @@
+//! Decompress a memory-mapped Brotli file without reading it into memory first.
+//!
+//! Usage: `cargo run --example mmap_decode -- <input> [output]`
+//!
+//! The decompressed bytes are written to `output`, or to stdout if it is omitted.
+
+#[cfg(unix)]
+mod mmap {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::ptr;
+
+    /// A read-only memory mapping of a whole file, unmapped on drop.
+    pub struct Mmap {
+        ptr: *mut libc::c_void,
+        len: usize,
+    }
+
+    impl Mmap {
+        /// Map the whole of `file` read-only.
+        pub fn map(file: &File) -> io::Result<Self> {
+            let len = usize::try_from(file.metadata()?.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large"))?;
+
+            // Mapping zero bytes is an error, so an empty file maps to an empty slice.
+            if len == 0 {
+                return Ok(Self {
+                    ptr: ptr::null_mut(),
+                    len,
+                });
+            }
+
+            // SAFETY: the arguments describe a read-only shared mapping of an open file; the
+            // result is checked against MAP_FAILED before use.
+            let ptr = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    len,
+                    libc::PROT_READ,
+                    libc::MAP_SHARED,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self { ptr, len })
+        }
+
+        /// The mapped bytes, borrowed for as long as the mapping lives.
+        pub fn as_slice(&self) -> &[u8] {
+            if self.len == 0 {
+                return &[];
+            }
+
+            // SAFETY: `ptr` points to `len` readable bytes until `self` is dropped, and the
+            // returned slice cannot outlive `self`.
+            unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+        }
+    }
+
+    impl Drop for Mmap {
+        fn drop(&mut self) {
+            if self.len != 0 {
+                // SAFETY: `ptr` and `len` come from a successful mmap call.
+                unsafe { libc::munmap(self.ptr, self.len) };
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn main() -> std::process::ExitCode {
+    use std::fs::{self, File};
+    use std::io::{self, Write};
+    use std::process::ExitCode;
+
+    use brotschneider::Decompressor;
+
+    let mut args = std::env::args().skip(1);
+    let (Some(input_path), output_path) = (args.next(), args.next()) else {
+        eprintln!("usage: mmap_decode <input> [output]");
+        return ExitCode::FAILURE;
+    };
+
+    let mapping = match File::open(&input_path).and_then(|file| mmap::Mmap::map(&file)) {
+        Ok(mapping) => mapping,
+        Err(e) => {
+            eprintln!("error: cannot map {input_path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // The decompressor reads the mapped bytes in place; `mapping` outlives the borrow.
+    let mut decompressor = Decompressor::new();
+    let output = match decompressor.decompress(mapping.as_slice()) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("error: cannot decompress {input_path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let written = match &output_path {
+        Some(path) => fs::write(path, &output),
+        None => io::stdout().lock().write_all(&output),
+    };
+    if let Err(e) = written {
+        eprintln!("error: cannot write output: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("mmap_decode is only supported on Unix platforms");
+}
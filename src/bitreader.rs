@@ -1,6 +1,11 @@
 use crate::error::BitReaderError;
 
 /// BitReader reads individual bits and bit sequences from a byte array.
+///
+/// The reader borrows its input for the lifetime `'a` and never copies it, so any byte
+/// slice works as a source, including one backed by a memory-mapped file. The mapping must
+/// outlive the reader; the borrow checker enforces this as long as the slice's lifetime is
+/// tied to the mapping.
 #[derive(Clone)]
 pub struct BitReader<'a> {
     /// The byte slice to read from.
@@ -14,6 +19,8 @@ pub struct BitReader<'a> {
 impl<'a> BitReader<'a> {
     /// Creates a new BitReader.
     ///
+    /// This is a `const fn`, so a reader over static data can be built in a constant.
+    ///
     /// # Arguments
     ///
     /// * `data` - A byte slice to read from.
@@ -32,8 +39,13 @@ impl<'a> BitReader<'a> {
     /// let bits = reader.read_bits(4).unwrap();
     ///
     /// assert_eq!(bits, 0b1100);
+    ///
+    /// const HEADER: BitReader<'static> = BitReader::new(&[0b0110_0000]);
+    /// let mut reader = HEADER;
+    /// assert_eq!(reader.read_bits(3).unwrap(), 0b011);
     /// ```
-    pub fn new(data: &'a [u8]) -> Self {
+    #[inline]
+    pub const fn new(data: &'a [u8]) -> Self {
         Self {
             data,
             byte_pos: 0,
@@ -41,6 +53,59 @@ impl<'a> BitReader<'a> {
         }
     }
 
+    /// Creates a new BitReader positioned at a given offset into `data`.
+    ///
+    /// This allows resuming a decode at a known position, such as a meta-block boundary
+    /// recorded by an earlier pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A byte slice to read from.
+    /// * `byte_pos` - The byte to start reading from.
+    /// * `bit_pos` - The bit within that byte to start reading from (0-7).
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing a new instance of BitReader.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::InvalidPosition` if `bit_pos` is not below 8 or the
+    ///   position is past the end of `data`. The end of `data` itself is a valid position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError};
+    ///
+    /// let data = [0b11001100, 0b10101010];
+    /// let mut reader = BitReader::new_at(&data, 1, 2).unwrap();
+    ///
+    /// assert_eq!(reader.bit_offset(), 10);
+    /// assert_eq!(reader.read_bits(4).unwrap(), 0b1010);
+    ///
+    /// assert!(BitReader::new_at(&data, 2, 0).unwrap().is_empty());
+    /// assert_eq!(
+    ///     BitReader::new_at(&data, 2, 1).err(),
+    ///     Some(BitReaderError::InvalidPosition {
+    ///         byte_pos: 2,
+    ///         bit_pos: 1
+    ///     })
+    /// );
+    /// ```
+    pub fn new_at(data: &'a [u8], byte_pos: usize, bit_pos: u8) -> Result<Self, BitReaderError> {
+        let in_range = byte_pos < data.len() || (byte_pos == data.len() && bit_pos == 0);
+        if bit_pos >= 8 || !in_range {
+            return Err(BitReaderError::InvalidPosition { byte_pos, bit_pos });
+        }
+
+        Ok(Self {
+            data,
+            byte_pos,
+            bit_pos,
+        })
+    }
+
     /// Read `n` bits and advance the position.
     ///
     /// # Arguments
@@ -55,19 +120,245 @@ impl<'a> BitReader<'a> {
     /// # Examples
     ///
     /// ```rust
-    /// use brotschneider::BitReader;
+    /// use brotschneider::{BitReader, BitReaderError};
     ///
     /// let data = [0b11001100, 0b10101010];
     /// let mut reader = BitReader::new(&data);
     /// let bits = reader.read_bits(4).unwrap();
     ///
     /// assert_eq!(bits, 0b1100);
+    ///
+    /// // Reading from empty input fails instead of indexing out of bounds.
+    /// let mut reader = BitReader::new(&[]);
+    /// assert_eq!(reader.read_bits(1), Err(BitReaderError::UnexpectedEndOfInput));
     /// ```
     pub fn read_bits(&mut self, n: u8) -> Result<u32, BitReaderError> {
         if n == 0 || n > 32 {
             return Err(BitReaderError::InvalidBitCount(n));
         }
 
+        self.read_bits_unchecked(n)
+    }
+
+    /// Read `n` bits holding a field whose largest allowed value is `max`.
+    ///
+    /// The bits are consumed even if the value is out of range.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of bits to read (1-32).
+    /// * `max` - The largest value the field may hold.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the read bits.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::InvalidBitCount` if `n` is 0 or greater than 32.
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    /// * Returns `BitReaderError::ValueOutOfRange` if the value read is greater than `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError};
+    ///
+    /// let data = [0b0101_0110];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.read_bounded(4, 5), Ok(5));
+    /// assert_eq!(
+    ///     reader.read_bounded(4, 5),
+    ///     Err(BitReaderError::ValueOutOfRange { value: 6, max: 5 })
+    /// );
+    /// assert!(reader.is_empty());
+    /// ```
+    #[inline]
+    pub fn read_bounded(&mut self, n: u8, max: u32) -> Result<u32, BitReaderError> {
+        let value = self.read_bits(n)?;
+        if value > max {
+            return Err(BitReaderError::ValueOutOfRange { value, max });
+        }
+
+        Ok(value)
+    }
+
+    /// Read `N` bits, where `N` is known at compile time.
+    ///
+    /// The bit count is validated at compile time, so the hot path carries no runtime check
+    /// and the read can be specialized for the width.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the read bits.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0b11001100, 0b10101010];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.read_const::<1>().unwrap(), 1);
+    /// assert_eq!(reader.read_const::<11>().unwrap(), 0b10011001010);
+    /// ```
+    ///
+    /// A width outside 1-32 fails to compile:
+    ///
+    /// ```compile_fail
+    /// use brotschneider::BitReader;
+    ///
+    /// let mut reader = BitReader::new(&[0]);
+    /// reader.read_const::<0>().unwrap();
+    /// ```
+    #[inline]
+    pub fn read_const<const N: u8>(&mut self) -> Result<u32, BitReaderError> {
+        const { assert!(N >= 1 && N <= 32, "bit count must be between 1 and 32") };
+
+        self.read_bits_unchecked(N)
+    }
+
+    /// Read a single bit as a flag.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing `true` if the bit is set.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bit(true).unwrap();
+    /// writer.write_bit(false).unwrap();
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// assert!(reader.read_bit().unwrap());
+    /// assert!(!reader.read_bit().unwrap());
+    /// ```
+    #[inline]
+    pub fn read_bit(&mut self) -> Result<bool, BitReaderError> {
+        Ok(self.read_bits_unchecked(1)? != 0)
+    }
+
+    /// Read `n` bits and return them in reverse order.
+    ///
+    /// `read_bits` places the first bit read in the most significant position of the result;
+    /// this places it in bit 0 instead, so the result is `read_bits(n)` with its lowest `n`
+    /// bits mirrored. This is the convention RFC 7932 uses to print prefix codes, with the
+    /// first bit on the right, and that of tables indexed by peeked codes in LSB-first
+    /// decoders.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of bits to read (1-32).
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the read bits, first bit in bit 0.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::InvalidBitCount` if `n` is 0 or greater than 32.
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// // RFC 7932 section 3.5 prints the code length code for symbol 1 as 0111, first bit
+    /// // on the right, so the bits appear in the stream as 1, 1, 1, 0.
+    /// let data = [0b1110_0000];
+    /// assert_eq!(BitReader::new(&data).read_bits(4).unwrap(), 0b1110);
+    /// assert_eq!(BitReader::new(&data).read_bits_rev(4).unwrap(), 0b0111);
+    ///
+    /// let data = [0b1000_0000, 0, 0, 0b0000_0011];
+    /// assert_eq!(BitReader::new(&data).read_bits_rev(32).unwrap(), 0xc000_0001);
+    /// ```
+    #[inline]
+    pub fn read_bits_rev(&mut self, n: u8) -> Result<u32, BitReaderError> {
+        let bits = self.read_bits(n)?;
+
+        Ok(bits.reverse_bits() >> (32 - n))
+    }
+
+    /// Read `n` bits, least significant bit first, taking each byte from its least
+    /// significant bit.
+    ///
+    /// This is the bit order of RFC 7932 and the mirror of `BitWriter::write_bits_lsb`. The
+    /// first bit read becomes bit 0 of the result. Bits read this way should not share a byte
+    /// with bits read by `read_bits`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of bits to read (1-32).
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the read bits.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::InvalidBitCount` if `n` is 0 or greater than 32.
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0b1100_1101, 0b0000_0001];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.read_bits_lsb(3).unwrap(), 0b101);
+    /// assert_eq!(reader.read_bits_lsb(6).unwrap(), 0b11_1001);
+    /// ```
+    pub fn read_bits_lsb(&mut self, n: u8) -> Result<u32, BitReaderError> {
+        if n == 0 || n > 32 {
+            return Err(BitReaderError::InvalidBitCount(n));
+        }
+
+        let mut result = 0u32;
+        let mut shift = 0u8;
+
+        while shift < n {
+            let Some(&current_byte) = self.data.get(self.byte_pos) else {
+                return Err(BitReaderError::UnexpectedEndOfInput);
+            };
+
+            let bits_to_take = (n - shift).min(8 - self.bit_pos);
+            let mask = ((1u16 << bits_to_take) - 1) as u8;
+            let bits = (current_byte >> self.bit_pos) & mask;
+
+            result |= (bits as u32) << shift;
+            shift += bits_to_take;
+
+            self.bit_pos += bits_to_take;
+            if self.bit_pos == 8 {
+                self.byte_pos += 1;
+                self.bit_pos = 0;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Read `n` bits, where `n` is already known to be between 1 and 32.
+    #[inline(always)]
+    fn read_bits_unchecked(&mut self, n: u8) -> Result<u32, BitReaderError> {
         let mut bits_left = n;
         let mut result = 0u32;
 
@@ -98,6 +389,146 @@ impl<'a> BitReader<'a> {
         Ok(result)
     }
 
+    /// Read `nextra` extra bits and add them to `base`.
+    ///
+    /// This is the "base value plus extra bits" pattern used by length and distance codes.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The base value of the code.
+    /// * `nextra` - The number of extra bits to read (0-32).
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing `base` plus the extra bits, or just `base` if `nextra` is 0.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    /// * Returns `BitReaderError::InvalidBitCount` if `nextra` is greater than 32.
+    /// * Returns `BitReaderError::ValueOutOfRange` if the sum does not fit in a `u32`, with
+    ///   the extra bits as the value and the largest extra value `base` allows as the
+    ///   maximum.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError};
+    ///
+    /// let data = [0b1010_0000];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.read_value(10, 0).unwrap(), 10);
+    /// assert_eq!(reader.read_value(10, 3).unwrap(), 15);
+    /// assert_eq!(reader.bit_offset(), 3);
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// assert_eq!(
+    ///     reader.read_value(u32::MAX - 1, 3),
+    ///     Err(BitReaderError::ValueOutOfRange { value: 5, max: 1 })
+    /// );
+    /// ```
+    #[inline]
+    pub fn read_value(&mut self, base: u32, nextra: u8) -> Result<u32, BitReaderError> {
+        if nextra == 0 {
+            return Ok(base);
+        }
+
+        let extra = self.read_bits(nextra)?;
+        base.checked_add(extra)
+            .ok_or(BitReaderError::ValueOutOfRange {
+                value: extra,
+                max: u32::MAX - base,
+            })
+    }
+
+    /// Returns the next `n` bits as a string of `'0'` and `'1'` characters, without
+    /// advancing.
+    ///
+    /// This is meant for debugging and test authoring. If fewer than `n` bits remain, only
+    /// the remaining bits are included.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of bits to show.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0b11001100, 0b10101010];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// reader.read_bits(6).unwrap();
+    /// assert_eq!(reader.peek_bit_string(5), "00101");
+    /// assert_eq!(reader.peek_bit_string(20), "0010101010");
+    /// assert_eq!(reader.bit_offset(), 6);
+    /// ```
+    pub fn peek_bit_string(&self, n: u8) -> String {
+        let mut clone = self.clone();
+
+        (0..n)
+            .map_while(|_| clone.read_bits(1).ok())
+            .map(|bit| if bit == 1 { '1' } else { '0' })
+            .collect()
+    }
+
+    /// Read a value encoded with a small prefix code, given as a table of patterns.
+    ///
+    /// Each entry is `(bits, len, value)`: if the next `len` bits equal `bits`, they are
+    /// consumed and `value` is returned. Entries are tried in order, so the table must be
+    /// prefix-free or list longer patterns before the shorter ones they extend.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The `(bits, len, value)` patterns, with `len` between 1 and 8.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the value of the matching pattern.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::NoPrefixMatch` if no pattern matches.
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if no pattern matches and the input
+    ///   ended before a longer pattern could be checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError};
+    ///
+    /// let table = [(0b0, 1, 10), (0b10, 2, 20), (0b110, 3, 30)];
+    /// let mut reader = BitReader::new(&[0b0101_1011, 0b1000_0000]);
+    ///
+    /// assert_eq!(reader.read_prefix(&table), Ok(10));
+    /// assert_eq!(reader.read_prefix(&table), Ok(20));
+    /// assert_eq!(reader.read_prefix(&table), Ok(30));
+    /// assert_eq!(reader.read_prefix(&table), Err(BitReaderError::NoPrefixMatch));
+    /// ```
+    pub fn read_prefix(&mut self, table: &[(u8, u8, u32)]) -> Result<u32, BitReaderError> {
+        let mut truncated = false;
+
+        for &(bits, len, value) in table {
+            match self.peek_bits(len) {
+                Ok(peeked) if peeked == bits as u32 => {
+                    self.skip_bits(len as usize)?;
+                    return Ok(value);
+                }
+                Ok(_) => {}
+                Err(BitReaderError::UnexpectedEndOfInput) => truncated = true,
+                Err(e) => return Err(e),
+            }
+        }
+
+        if truncated {
+            return Err(BitReaderError::UnexpectedEndOfInput);
+        }
+
+        Err(BitReaderError::NoPrefixMatch)
+    }
+
     /// Peek `n` bits without advancing the position.
     ///
     /// # Arguments
@@ -125,6 +556,41 @@ impl<'a> BitReader<'a> {
         clone.read_bits(n)
     }
 
+    /// Count the set bits among the next `n` bits without advancing the position.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of bits to inspect (1-32).
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the number of 1 bits.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `BitReader::peek_bits`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0b1100_1100, 0b1010_1010];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.count_ones(4).unwrap(), 2);
+    /// assert_eq!(reader.count_ones(16).unwrap(), 8);
+    ///
+    /// // One of the two bits left before the end is set.
+    /// reader.read_bits(14).unwrap();
+    /// assert_eq!(reader.count_ones(2).unwrap(), 1);
+    /// assert!(reader.count_ones(3).is_err());
+    /// ```
+    #[inline]
+    pub fn count_ones(&self, n: u8) -> Result<u32, BitReaderError> {
+        Ok(self.peek_bits(n)?.count_ones())
+    }
+
     /// Skip `n` bits without reading them.
     ///
     /// # Arguments
@@ -168,6 +634,49 @@ impl<'a> BitReader<'a> {
         Ok(())
     }
 
+    /// Move the cursor back by `n` bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of bits to move back.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the cursor was moved.
+    /// * `Err(BitReaderError)` if it would move before the start of the data.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::RewindBeforeStart` with the number of bits it would have
+    ///   gone past the start. The cursor is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError};
+    ///
+    /// let data = [0b11001100, 0b10101010];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.read_bits(10).unwrap(), 0b1100110010);
+    /// reader.rewind(3).unwrap();
+    /// assert_eq!(reader.read_bits(5).unwrap(), 0b01010);
+    ///
+    /// assert_eq!(reader.rewind(14), Err(BitReaderError::RewindBeforeStart(2)));
+    /// assert_eq!(reader.bit_offset(), 12);
+    /// ```
+    pub fn rewind(&mut self, n: usize) -> Result<(), BitReaderError> {
+        let offset = self.bit_offset();
+        let target = offset
+            .checked_sub(n)
+            .ok_or_else(|| BitReaderError::RewindBeforeStart(n - offset))?;
+
+        self.byte_pos = target / 8;
+        self.bit_pos = (target % 8) as u8;
+
+        Ok(())
+    }
+
     /// Align to the next byte boundary.
     ///
     /// # Examples
@@ -190,6 +699,198 @@ impl<'a> BitReader<'a> {
         }
     }
 
+    /// Align to the next byte boundary, checking that the skipped bits are zero.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the number of bits skipped (0-7).
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::NonZeroPadding` if any skipped bit is set. The reader is
+    ///   still aligned in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError};
+    ///
+    /// let data = [0b1010_0000, 0b1000_0001];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// reader.read_bits(3).unwrap();
+    /// assert_eq!(reader.align_to_byte_checked(), Ok(5));
+    /// assert_eq!(reader.align_to_byte_checked(), Ok(0));
+    ///
+    /// reader.read_bits(1).unwrap();
+    /// assert_eq!(
+    ///     reader.align_to_byte_checked(),
+    ///     Err(BitReaderError::NonZeroPadding)
+    /// );
+    /// assert!(reader.is_empty());
+    /// ```
+    pub fn align_to_byte_checked(&mut self) -> Result<u8, BitReaderError> {
+        if self.bit_pos == 0 {
+            return Ok(0);
+        }
+
+        let skipped = 8 - self.bit_pos;
+        let padding = self.read_bits(skipped)?;
+        if padding != 0 {
+            return Err(BitReaderError::NonZeroPadding);
+        }
+
+        Ok(skipped)
+    }
+
+    /// Read a byte-aligned little-endian u16.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the value assembled from the next two bytes.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::NotByteAligned` if the reader is in the middle of a byte.
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if fewer than two bytes remain.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError};
+    ///
+    /// let data = [0x34, 0x12, 0xff];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.read_u16_le(), Ok(0x1234));
+    /// assert_eq!(reader.read_u16_le(), Err(BitReaderError::UnexpectedEndOfInput));
+    ///
+    /// reader.read_bits(1).unwrap();
+    /// assert_eq!(reader.read_u16_le(), Err(BitReaderError::NotByteAligned));
+    /// ```
+    #[inline]
+    pub fn read_u16_le(&mut self) -> Result<u16, BitReaderError> {
+        self.read_le_bytes().map(u16::from_le_bytes)
+    }
+
+    /// Read a byte-aligned little-endian u32.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the value assembled from the next four bytes.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::NotByteAligned` if the reader is in the middle of a byte.
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if fewer than four bytes remain.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0x78, 0x56, 0x34, 0x12];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert_eq!(reader.read_u32_le(), Ok(0x1234_5678));
+    /// assert!(reader.is_empty());
+    /// ```
+    #[inline]
+    pub fn read_u32_le(&mut self) -> Result<u32, BitReaderError> {
+        self.read_le_bytes().map(u32::from_le_bytes)
+    }
+
+    /// Take the next `N` bytes, which must start at a byte boundary.
+    fn read_le_bytes<const N: usize>(&mut self) -> Result<[u8; N], BitReaderError> {
+        if self.bit_pos != 0 {
+            return Err(BitReaderError::NotByteAligned);
+        }
+
+        let bytes = self
+            .remaining_slice()
+            .first_chunk::<N>()
+            .ok_or(BitReaderError::UnexpectedEndOfInput)?;
+        self.byte_pos += N;
+
+        Ok(*bytes)
+    }
+
+    /// Returns the number of bits consumed so far.
+    ///
+    /// # Returns
+    ///
+    /// * The current position in the data, in bits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0b11001100, 0b10101010];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// reader.read_bits(11).unwrap();
+    /// assert_eq!(reader.bit_offset(), 11);
+    /// ```
+    #[inline]
+    pub fn bit_offset(&self) -> usize {
+        self.byte_pos * 8 + self.bit_pos as usize
+    }
+
+    /// Returns the number of bits left to read.
+    ///
+    /// # Returns
+    ///
+    /// * The number of unread bits in the data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0b11001100, 0b10101010];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// reader.read_bits(11).unwrap();
+    /// assert_eq!(reader.bits_remaining(), 5);
+    /// ```
+    #[inline]
+    pub fn bits_remaining(&self) -> usize {
+        (self.data.len() * 8).saturating_sub(self.bit_offset())
+    }
+
+    /// Returns the unread portion of the underlying data without consuming it.
+    ///
+    /// The slice starts at the current byte. If the reader is not byte-aligned,
+    /// the first byte of the slice has already been partially consumed.
+    ///
+    /// # Returns
+    ///
+    /// * A slice of the remaining bytes, starting at the current byte position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let data = [0b11001100, 0b10101010, 0b11110000];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// reader.read_bits(8).unwrap();
+    /// assert_eq!(reader.remaining_slice(), &[0b10101010, 0b11110000]);
+    ///
+    /// // Partially consumed bytes are still included.
+    /// reader.read_bits(4).unwrap();
+    /// assert_eq!(reader.remaining_slice(), &[0b10101010, 0b11110000]);
+    ///
+    /// reader.read_bits(12).unwrap();
+    /// assert!(reader.remaining_slice().is_empty());
+    /// ```
+    #[inline]
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        self.data.get(self.byte_pos..).unwrap_or(&[])
+    }
+
     /// Check if all input has been consumed.
     ///
     /// # Returns
@@ -212,3 +913,30 @@ impl<'a> BitReader<'a> {
         self.byte_pos >= self.data.len() && self.bit_pos == 0
     }
 }
+
+impl<'a> From<&'a [u8]> for BitReader<'a> {
+    #[inline]
+    fn from(data: &'a [u8]) -> Self {
+        Self::new(data)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for BitReader<'a> {
+    /// Creates a reader over an array without converting it to a slice first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    ///
+    /// let mut reader = BitReader::from(&[0b1010_0000]);
+    /// assert_eq!(reader.read_bits(3).unwrap(), 0b101);
+    ///
+    /// let mut reader: BitReader = (&[0xff, 0x00]).into();
+    /// assert_eq!(reader.read_bits(12).unwrap(), 0xff0);
+    /// ```
+    #[inline]
+    fn from(data: &'a [u8; N]) -> Self {
+        Self::new(data)
+    }
+}
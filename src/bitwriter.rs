@@ -1,4 +1,9 @@
 use crate::error::BitWriterError;
+use crate::huffman::{
+    CODE_LENGTH_CODE_LENGTH_CODES, CODE_LENGTH_CODE_ORDER, CODE_LENGTH_CODES,
+    INITIAL_REPEATED_CODE_LENGTH, REPEAT_PREVIOUS_CODE_LENGTH, REPEAT_ZERO_CODE_LENGTH,
+    alphabet_bits, canonical_codes,
+};
 
 /// BitWriter writes individual bits and bit sequences to a byte array.
 #[derive(Default)]
@@ -111,6 +116,271 @@ impl BitWriter {
         Ok(())
     }
 
+    /// Writes the specified number of bits, least significant bit first, filling each byte
+    /// from its least significant bit.
+    ///
+    /// This is the bit order of RFC 7932 and the mirror of `BitReader::read_bits_lsb`. Bits
+    /// written this way should not share a byte with bits written by `write_bits`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value containing the bits to write.
+    /// * `num_bits` - The number of bits to write (0-32).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the bits were written successfully.
+    /// * `Err(BitWriterError)` if the number of bits is invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitWriterError::TooManyBits` if `num_bits` is greater than 32.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits_lsb(0b101, 3).unwrap();
+    /// writer.write_bits_lsb(0b1_1000_0001, 9).unwrap();
+    /// writer.write_bits_lsb(0xdead_beef, 32).unwrap();
+    /// let data = writer.into_inner();
+    /// assert_eq!(data[0], 0b0000_1101);
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// assert_eq!(reader.read_bits_lsb(3).unwrap(), 0b101);
+    /// assert_eq!(reader.read_bits_lsb(9).unwrap(), 0b1_1000_0001);
+    /// assert_eq!(reader.read_bits_lsb(32).unwrap(), 0xdead_beef);
+    /// ```
+    pub fn write_bits_lsb(
+        &mut self,
+        mut value: u32,
+        mut num_bits: u8,
+    ) -> Result<(), BitWriterError> {
+        if num_bits > 32 {
+            return Err(BitWriterError::TooManyBits(num_bits));
+        }
+
+        while num_bits > 0 {
+            let bits_to_write = num_bits.min(8 - self.bit_pos);
+            let bits = value & ((1 << bits_to_write) - 1);
+
+            self.current_byte |= (bits as u8) << self.bit_pos;
+            self.bit_pos += bits_to_write;
+            num_bits -= bits_to_write;
+            value = value.checked_shr(bits_to_write as u32).unwrap_or(0);
+
+            if self.bit_pos == 8 {
+                self.flush()?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes a single bit as a flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit` - The flag to write; `true` is written as 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the bit was written successfully.
+    /// * `Err(BitWriterError)` if an error occurs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitWriter;
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bit(true).unwrap();
+    /// writer.write_bits(0b0000001, 7).unwrap();
+    /// assert_eq!(writer.into_inner(), vec![0b1000_0001]);
+    /// ```
+    #[inline]
+    pub fn write_bit(&mut self, bit: bool) -> Result<(), BitWriterError> {
+        self.write_bits(bit as u32, 1)
+    }
+
+    /// Writes a simple prefix code listing up to four symbols.
+    ///
+    /// This is the inverse of `huffman::read_simple`. The first symbol receives the shortest
+    /// code; four symbols are written with equal two-bit codes.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - The one to four distinct symbols of the code.
+    /// * `alphabet_size` - The number of symbols in the alphabet.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the prefix code was written successfully.
+    /// * `Err(BitWriterError)` if the symbols are invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitWriterError::InvalidSimpleCode` if there are no symbols or more than
+    ///   four, a symbol is out of range for the alphabet, or a symbol is repeated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::huffman::read_simple;
+    /// use brotschneider::{BitReader, BitWriter};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_simple_code(&[200, 10, 30, 20], 256).unwrap();
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// let lengths = read_simple(&mut reader, 256).unwrap();
+    /// assert_eq!(lengths.iter().filter(|&&len| len == 2).count(), 4);
+    /// ```
+    pub fn write_simple_code(
+        &mut self,
+        symbols: &[u16],
+        alphabet_size: usize,
+    ) -> Result<(), BitWriterError> {
+        if symbols.is_empty() || symbols.len() > 4 {
+            return Err(BitWriterError::InvalidSimpleCode);
+        }
+
+        for (i, &symbol) in symbols.iter().enumerate() {
+            if symbol as usize >= alphabet_size || symbols[..i].contains(&symbol) {
+                return Err(BitWriterError::InvalidSimpleCode);
+            }
+        }
+
+        self.write_bits(1, 2)?;
+        self.write_bits(symbols.len() as u32 - 1, 2)?;
+
+        let bits = alphabet_bits(alphabet_size);
+        for &symbol in symbols {
+            self.write_bits(symbol as u32, bits)?;
+        }
+
+        if symbols.len() == 4 {
+            self.write_bits(0, 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a complex prefix code describing the given symbol code lengths.
+    ///
+    /// This is the inverse of `huffman::read_complex`. The code lengths are run-length
+    /// encoded, using repeat codes 16 and 17 for runs of three or more equal lengths, and
+    /// trailing zero lengths are omitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `lengths` - The code length (0-15) of each symbol in the alphabet.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the prefix code was written successfully.
+    /// * `Err(BitWriterError)` if the code lengths are invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitWriterError::InvalidCodeLengths` if a length exceeds 15, fewer than two
+    ///   symbols are present, or the lengths do not form a complete prefix code.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::huffman::read_complex;
+    /// use brotschneider::{BitReader, BitWriter};
+    ///
+    /// let mut lengths = vec![0u8; 40];
+    /// lengths[..6].fill(3);
+    /// lengths[6..8].fill(4);
+    /// lengths[20..22].fill(4);
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_complex_code(&lengths).unwrap();
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// assert_eq!(read_complex(&mut reader, 40).unwrap(), lengths);
+    /// ```
+    pub fn write_complex_code(&mut self, lengths: &[u8]) -> Result<(), BitWriterError> {
+        let mut space = 0u32;
+        let mut num_symbols = 0;
+        for &len in lengths {
+            if len > 15 {
+                return Err(BitWriterError::InvalidCodeLengths);
+            }
+
+            if len != 0 {
+                space += 32768 >> len;
+                num_symbols += 1;
+            }
+        }
+
+        if num_symbols < 2 || space != 32768 {
+            return Err(BitWriterError::InvalidCodeLengths);
+        }
+
+        let (codes, extras) = run_length_encode(lengths);
+
+        let mut histogram = [0u32; CODE_LENGTH_CODES];
+        for &code in &codes {
+            histogram[code as usize] += 1;
+        }
+
+        let code_length_code_lengths = code_length_code_lengths(&histogram);
+        let num_codes = code_length_code_lengths
+            .iter()
+            .filter(|&&len| len != 0)
+            .count();
+
+        let mut hskip = 0;
+        if code_length_code_lengths[CODE_LENGTH_CODE_ORDER[0]] == 0
+            && code_length_code_lengths[CODE_LENGTH_CODE_ORDER[1]] == 0
+        {
+            hskip = 2;
+            if code_length_code_lengths[CODE_LENGTH_CODE_ORDER[2]] == 0 {
+                hskip = 3;
+            }
+        }
+
+        // The reader stops once the code length code is complete, so trailing zeros are
+        // omitted. A single code length code never completes the code, so all are stored.
+        let mut codes_to_store = CODE_LENGTH_CODES;
+        if num_codes > 1 {
+            while code_length_code_lengths[CODE_LENGTH_CODE_ORDER[codes_to_store - 1]] == 0 {
+                codes_to_store -= 1;
+            }
+        }
+
+        self.write_bits(hskip as u32, 2)?;
+        for &symbol in &CODE_LENGTH_CODE_ORDER[hskip..codes_to_store] {
+            let (code, len) =
+                CODE_LENGTH_CODE_LENGTH_CODES[code_length_code_lengths[symbol] as usize];
+            self.write_bits(code, len)?;
+        }
+
+        let code_length_codes = canonical_codes(&code_length_code_lengths);
+        for (&code, &extra) in codes.iter().zip(&extras) {
+            if num_codes > 1 {
+                let len = code_length_code_lengths[code as usize];
+                self.write_bits(code_length_codes[code as usize], len)?;
+            }
+
+            match code {
+                REPEAT_PREVIOUS_CODE_LENGTH => self.write_bits(extra as u32, 2)?,
+                REPEAT_ZERO_CODE_LENGTH => self.write_bits(extra as u32, 3)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
     /// Flushes the current byte to the buffer if there are any bits left.
     ///
     /// # Returns
@@ -138,6 +408,47 @@ impl BitWriter {
         Ok(())
     }
 
+    /// Flushes the current byte, then writes zero bytes until the output length is a
+    /// multiple of `byte_multiple`.
+    ///
+    /// A `byte_multiple` of 0 or 1 only flushes the current byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte_multiple` - The boundary, in bytes, to pad the output to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the padding was written successfully.
+    /// * `Err(BitWriterError)` if an error occurs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitWriter;
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1_1111_1111, 9).unwrap();
+    /// writer.pad_to(4).unwrap();
+    /// assert_eq!(writer.into_inner(), [0xff, 0b1000_0000, 0, 0]);
+    ///
+    /// // Output that is already aligned is left as is.
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0xabcd, 16).unwrap();
+    /// writer.pad_to(2).unwrap();
+    /// assert_eq!(writer.into_inner(), [0xab, 0xcd]);
+    /// ```
+    pub fn pad_to(&mut self, byte_multiple: usize) -> Result<(), BitWriterError> {
+        self.flush()?;
+
+        if byte_multiple > 1 {
+            let len = self.buffer.len().next_multiple_of(byte_multiple);
+            self.buffer.resize(len, 0);
+        }
+
+        Ok(())
+    }
+
     /// Returns the current buffer as a byte vector.
     ///
     /// # Returns
@@ -160,3 +471,136 @@ impl BitWriter {
         self.buffer
     }
 }
+
+/// Run-length encode symbol code lengths into code length symbols and their extra bits.
+fn run_length_encode(lengths: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut codes = Vec::new();
+    let mut extras = Vec::new();
+
+    let end = lengths
+        .iter()
+        .rposition(|&len| len != 0)
+        .map_or(0, |i| i + 1);
+    let mut previous = INITIAL_REPEATED_CODE_LENGTH;
+    let mut i = 0;
+
+    while i < end {
+        let value = lengths[i];
+        let reps = lengths[i..end]
+            .iter()
+            .take_while(|&&len| len == value)
+            .count();
+
+        if value == 0 {
+            push_zero_repetitions(&mut codes, &mut extras, reps);
+        } else {
+            push_repetitions(&mut codes, &mut extras, previous, value, reps);
+            previous = value;
+        }
+
+        i += reps;
+    }
+
+    (codes, extras)
+}
+
+/// Encode a run of `reps` zero code lengths.
+fn push_zero_repetitions(codes: &mut Vec<u8>, extras: &mut Vec<u8>, mut reps: usize) {
+    if reps == 11 {
+        codes.push(0);
+        extras.push(0);
+        reps -= 1;
+    }
+
+    if reps < 3 {
+        codes.extend(std::iter::repeat_n(0, reps));
+        extras.extend(std::iter::repeat_n(0, reps));
+        return;
+    }
+
+    push_repeat_codes(codes, extras, REPEAT_ZERO_CODE_LENGTH, 3, reps - 3);
+}
+
+/// Encode a run of `reps` non-zero code lengths equal to `value`.
+fn push_repetitions(
+    codes: &mut Vec<u8>,
+    extras: &mut Vec<u8>,
+    previous: u8,
+    value: u8,
+    mut reps: usize,
+) {
+    if previous != value {
+        codes.push(value);
+        extras.push(0);
+        reps -= 1;
+    }
+
+    if reps == 7 {
+        codes.push(value);
+        extras.push(0);
+        reps -= 1;
+    }
+
+    if reps < 3 {
+        codes.extend(std::iter::repeat_n(value, reps));
+        extras.extend(std::iter::repeat_n(0, reps));
+        return;
+    }
+
+    push_repeat_codes(codes, extras, REPEAT_PREVIOUS_CODE_LENGTH, 2, reps - 3);
+}
+
+/// Emit a chain of repeat codes whose combined repeat count is `reps + 3`.
+///
+/// Consecutive repeat codes combine their extra bits, most significant first.
+fn push_repeat_codes(
+    codes: &mut Vec<u8>,
+    extras: &mut Vec<u8>,
+    code: u8,
+    extra_bits: u8,
+    mut reps: usize,
+) {
+    let start = codes.len();
+    let mask = (1 << extra_bits) - 1;
+
+    loop {
+        codes.push(code);
+        extras.push((reps & mask) as u8);
+
+        reps >>= extra_bits;
+        if reps == 0 {
+            break;
+        }
+
+        reps -= 1;
+    }
+
+    codes[start..].reverse();
+    extras[start..].reverse();
+}
+
+/// Assign code lengths (at most 5 bits) to the code length symbols in use.
+///
+/// Symbols are sorted by descending frequency and given a complete, near-balanced code, so
+/// more frequent symbols never receive longer codes.
+fn code_length_code_lengths(histogram: &[u32; CODE_LENGTH_CODES]) -> [u8; CODE_LENGTH_CODES] {
+    let mut symbols: Vec<usize> = (0..CODE_LENGTH_CODES)
+        .filter(|&s| histogram[s] != 0)
+        .collect();
+    symbols.sort_by(|&a, &b| histogram[b].cmp(&histogram[a]).then(a.cmp(&b)));
+
+    let mut lengths = [0u8; CODE_LENGTH_CODES];
+    if symbols.len() == 1 {
+        lengths[symbols[0]] = 1;
+        return lengths;
+    }
+
+    let depth = symbols.len().next_power_of_two().trailing_zeros() as u8;
+    let num_short = (1 << depth) - symbols.len();
+
+    for (i, &symbol) in symbols.iter().enumerate() {
+        lengths[symbol] = if i < num_short { depth - 1 } else { depth };
+    }
+
+    lengths
+}
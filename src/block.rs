@@ -0,0 +1,162 @@
+use crate::error::MetaBlockError;
+use crate::metablock::{huffman_with_offset, with_offset};
+use crate::{BitReader, HuffmanTree};
+
+/// Number of symbols in the block length alphabet.
+pub const BLOCK_LENGTH_ALPHABET_SIZE: usize = 26;
+
+/// Base block length for each block length symbol.
+const BLOCK_LENGTH_BASE: [u32; BLOCK_LENGTH_ALPHABET_SIZE] = [
+    1, 5, 9, 13, 17, 25, 33, 41, 49, 65, 81, 97, 113, 145, 177, 209, 241, 305, 369, 497, 753, 1265,
+    2289, 4337, 8433, 16625,
+];
+
+/// Number of extra bits for each block length symbol.
+const BLOCK_LENGTH_EXTRA: [u8; BLOCK_LENGTH_ALPHABET_SIZE] = [
+    2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 7, 8, 9, 10, 11, 12, 13, 24,
+];
+
+/// Read a block length: a symbol of the block length code followed by its extra bits.
+///
+/// The result is the number of symbols coded with the current block type before the next
+/// block switch.
+///
+/// # Arguments
+///
+/// * `tree` - The block length prefix code of the block category.
+/// * `reader` - A mutable reference to a `BitReader` instance.
+///
+/// # Returns
+///
+/// * A Result containing the block length.
+///
+/// # Errors
+///
+/// * Returns `MetaBlockError::Corrupt` if the decoded symbol is not a block length symbol.
+/// * Returns `MetaBlockError::Huffman` if the symbol cannot be decoded.
+/// * Returns `MetaBlockError::NeedMoreInput` if the input ends early.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::block::{BLOCK_LENGTH_ALPHABET_SIZE, read_block_length};
+/// use brotschneider::{BitReader, HuffmanTree, MetaBlockError};
+///
+/// // Symbol 4 (base 17, 3 extra bits) is coded as 0 and symbol 25 (base 16625, 24 extra
+/// // bits) as 1.
+/// let mut lengths = [0; BLOCK_LENGTH_ALPHABET_SIZE];
+/// lengths[4] = 1;
+/// lengths[25] = 1;
+/// let tree = HuffmanTree::from_code_lengths(&lengths).unwrap();
+///
+/// let data = [0b0_101_1_111, 0xff, 0xff, 0b1111_1000];
+/// let mut reader = BitReader::new(&data);
+/// assert_eq!(read_block_length(&tree, &mut reader).unwrap(), 17 + 0b101);
+/// assert_eq!(read_block_length(&tree, &mut reader).unwrap(), 16625 + (1 << 24) - 1);
+///
+/// // A tree over a larger alphabet can decode symbols with no block length.
+/// let mut lengths = [0; BLOCK_LENGTH_ALPHABET_SIZE + 1];
+/// lengths[0] = 1;
+/// lengths[BLOCK_LENGTH_ALPHABET_SIZE] = 1;
+/// let tree = HuffmanTree::from_code_lengths(&lengths).unwrap();
+/// assert!(matches!(
+///     read_block_length(&tree, &mut BitReader::new(&[0b1000_0000])),
+///     Err(MetaBlockError::Corrupt { .. })
+/// ));
+/// ```
+pub fn read_block_length(
+    tree: &HuffmanTree,
+    reader: &mut BitReader,
+) -> Result<u32, MetaBlockError> {
+    let symbol = tree
+        .decode_symbol(reader)
+        .map_err(|e| huffman_with_offset(reader, e))? as usize;
+
+    let (Some(&base), Some(&extra)) = (
+        BLOCK_LENGTH_BASE.get(symbol),
+        BLOCK_LENGTH_EXTRA.get(symbol),
+    ) else {
+        return Err(MetaBlockError::Corrupt {
+            reason: "invalid block length symbol",
+        });
+    };
+
+    reader
+        .read_value(base, extra)
+        .map_err(|e| with_offset(reader, e))
+}
+
+/// Read the number of block types of a block category (NBLTYPES).
+///
+/// The count is coded as a single 0 bit for 1, or a 1 bit followed by a 3-bit exponent `n`
+/// and `n` extra bits for the values `2^n + 1` to `2^(n + 1)`.
+///
+/// # Arguments
+///
+/// * `reader` - A mutable reference to a `BitReader` instance.
+///
+/// # Returns
+///
+/// * A Result containing the number of block types, from 1 to 256.
+///
+/// # Errors
+///
+/// * Returns `MetaBlockError::NeedMoreInput` if the input ends early.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::block::read_nbltypes;
+/// use brotschneider::{BitReader, BitWriter, MetaBlockError};
+///
+/// fn write_nbltypes(writer: &mut BitWriter, value: u16) {
+///     if value == 1 {
+///         writer.write_bits(0, 1).unwrap();
+///         return;
+///     }
+///
+///     let n = 15 - (value - 1).leading_zeros() as u8;
+///     writer.write_bits(1, 1).unwrap();
+///     writer.write_bits(n as u32, 3).unwrap();
+///     if n > 0 {
+///         writer.write_bits((value - 1 - (1 << n)) as u32, n).unwrap();
+///     }
+/// }
+///
+/// let mut writer = BitWriter::new();
+/// for value in 1..=256 {
+///     write_nbltypes(&mut writer, value);
+/// }
+/// let data = writer.into_inner();
+///
+/// let mut reader = BitReader::new(&data);
+/// for value in 1..=256 {
+///     assert_eq!(read_nbltypes(&mut reader).unwrap(), value);
+/// }
+///
+/// // 1, exponent 7 and 7 extra bits: the largest count.
+/// let mut reader = BitReader::new(&[0b1111_1111, 0b1110_0000]);
+/// assert_eq!(read_nbltypes(&mut reader).unwrap(), 256);
+///
+/// // The extra bits are missing.
+/// assert!(matches!(
+///     read_nbltypes(&mut BitReader::new(&[0b1111_0000])),
+///     Err(MetaBlockError::NeedMoreInput { bit_offset: 8 })
+/// ));
+/// ```
+pub fn read_nbltypes(reader: &mut BitReader) -> Result<u16, MetaBlockError> {
+    if !reader.read_bit().map_err(|e| with_offset(reader, e))? {
+        return Ok(1);
+    }
+
+    let n = reader.read_bits(3).map_err(|e| with_offset(reader, e))? as u8;
+
+    let value = reader
+        .read_value((1 << n) + 1, n)
+        .map_err(|e| with_offset(reader, e))?;
+
+    // The exponent has 3 bits, so the count is at most 2^7 + 1 + (2^7 - 1) = 256.
+    u16::try_from(value).map_err(|_| MetaBlockError::Corrupt {
+        reason: "NBLTYPES out of range",
+    })
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use crate::BitReader;
+use crate::error::BitReaderError;
+
+/// Number of symbols in the insert-and-copy length alphabet.
+pub const INSERT_COPY_ALPHABET_SIZE: usize = 704;
+
+/// Base insert length for each insert length code.
+pub(crate) const INSERT_LENGTH_BASE: [u32; 24] = [
+    0, 1, 2, 3, 4, 5, 6, 8, 10, 14, 18, 26, 34, 50, 66, 98, 130, 194, 322, 578, 1090, 2114, 6210,
+    22594,
+];
+
+/// Number of extra bits for each insert length code.
+pub(crate) const INSERT_LENGTH_EXTRA: [u8; 24] = [
+    0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9, 10, 12, 14, 24,
+];
+
+/// Base copy length for each copy length code.
+const COPY_LENGTH_BASE: [u32; 24] = [
+    2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 14, 18, 22, 30, 38, 54, 70, 102, 134, 198, 326, 582, 1094, 2118,
+];
+
+/// Number of extra bits for each copy length code.
+const COPY_LENGTH_EXTRA: [u8; 24] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9, 10, 24,
+];
+
+/// First insert length code of each 64-symbol range of the insert-and-copy alphabet.
+const INSERT_RANGE_OFFSET: [u8; 11] = [0, 0, 0, 0, 8, 8, 0, 16, 8, 16, 16];
+
+/// First copy length code of each 64-symbol range of the insert-and-copy alphabet.
+const COPY_RANGE_OFFSET: [u8; 11] = [0, 8, 0, 8, 0, 8, 16, 0, 16, 8, 16];
+
+/// The insert and copy lengths decoded from an insert-and-copy symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsertCopy {
+    /// The number of literals to insert.
+    pub insert_len: u32,
+    /// The number of bytes to copy.
+    pub copy_len: u32,
+    /// Whether the command reuses the last distance without reading a distance code.
+    pub implicit_distance: bool,
+}
+
+impl InsertCopy {
+    /// Decode an insert-and-copy symbol, reading the insert and copy extra bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The insert-and-copy symbol (0-703).
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decoded lengths.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if `symbol` is not less than `INSERT_COPY_ALPHABET_SIZE`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    /// use brotschneider::command::InsertCopy;
+    ///
+    /// // Symbol 8 is insert code 1 and copy code 0, with an implicit distance.
+    /// let mut reader = BitReader::new(&[]);
+    /// let lengths = InsertCopy::read(8, &mut reader).unwrap();
+    /// assert_eq!((lengths.insert_len, lengths.copy_len), (1, 2));
+    /// assert!(lengths.implicit_distance);
+    ///
+    /// // Symbol 258 is insert code 8 (2 extra bits) and copy code 2.
+    /// let mut reader = BitReader::new(&[0b1100_0000]);
+    /// let lengths = InsertCopy::read(258, &mut reader).unwrap();
+    /// assert_eq!((lengths.insert_len, lengths.copy_len), (13, 4));
+    /// assert!(!lengths.implicit_distance);
+    /// ```
+    pub fn read(symbol: u16, reader: &mut BitReader) -> Result<Self, BitReaderError> {
+        let range = (symbol >> 6) as usize;
+        let insert_code = (INSERT_RANGE_OFFSET[range] + ((symbol >> 3) & 7) as u8) as usize;
+        let copy_code = (COPY_RANGE_OFFSET[range] + (symbol & 7) as u8) as usize;
+
+        let insert_len = reader.read_value(
+            INSERT_LENGTH_BASE[insert_code],
+            INSERT_LENGTH_EXTRA[insert_code],
+        )?;
+        let copy_len =
+            reader.read_value(COPY_LENGTH_BASE[copy_code], COPY_LENGTH_EXTRA[copy_code])?;
+
+        Ok(Self {
+            insert_len,
+            copy_len,
+            implicit_distance: range < 2,
+        })
+    }
+}
+
+/// Where the distance of a decoded command came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceSource {
+    /// Derived from an entry of the distance ring buffer (0 is the most recent distance).
+    Ring(u8),
+    /// Encoded explicitly in the stream.
+    Explicit,
+    /// Beyond the sliding window, referencing the static dictionary.
+    Dictionary,
+}
+
+/// A decoded insert-and-copy command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Command {
+    /// The number of literals inserted before the copy.
+    pub insert_len: u32,
+    /// The number of bytes copied from the backward reference.
+    pub copy_len: u32,
+    /// The backward distance of the copy.
+    pub distance: u32,
+    /// Where the distance came from.
+    pub source: DistanceSource,
+}
+
+impl fmt::Display for Command {
+    /// Formats the command for debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::command::{Command, DistanceSource};
+    ///
+    /// let command = Command {
+    ///     insert_len: 5,
+    ///     copy_len: 12,
+    ///     distance: 300,
+    ///     source: DistanceSource::Ring(1),
+    /// };
+    /// assert_eq!(command.to_string(), "insert 5, copy 12 @ dist 300 (ring[1])");
+    ///
+    /// let command = Command {
+    ///     source: DistanceSource::Dictionary,
+    ///     ..command
+    /// };
+    /// assert_eq!(command.to_string(), "insert 5, copy 12 @ dist 300 (dictionary)");
+    ///
+    /// let command = Command {
+    ///     source: DistanceSource::Explicit,
+    ///     ..command
+    /// };
+    /// assert_eq!(command.to_string(), "insert 5, copy 12 @ dist 300");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "insert {}, copy {} @ dist {}",
+            self.insert_len, self.copy_len, self.distance
+        )?;
+
+        match self.source {
+            DistanceSource::Ring(index) => write!(f, " (ring[{index}])"),
+            DistanceSource::Explicit => Ok(()),
+            DistanceSource::Dictionary => write!(f, " (dictionary)"),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use crate::BitReader;
+use crate::error::BitReaderError;
+
+/// The context mode used to compute literal context IDs for a literal block type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextMode {
+    /// Context is the 6 least significant bits of the previous byte.
+    Lsb6,
+    /// Context is the 6 most significant bits of the previous byte.
+    Msb6,
+    /// Context is derived from the UTF-8 byte classes of the previous two bytes.
+    Utf8,
+    /// Context is derived from the signedness classes of the previous two bytes.
+    Signed,
+}
+
+impl ContextMode {
+    /// Maps a 2-bit context mode code to its `ContextMode`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - The 2-bit code (only the lowest two bits are used).
+    ///
+    /// # Returns
+    ///
+    /// * The context mode for the given code.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::ContextMode;
+    ///
+    /// assert_eq!(ContextMode::from_bits(0), ContextMode::Lsb6);
+    /// assert_eq!(ContextMode::from_bits(1), ContextMode::Msb6);
+    /// assert_eq!(ContextMode::from_bits(2), ContextMode::Utf8);
+    /// assert_eq!(ContextMode::from_bits(3), ContextMode::Signed);
+    /// ```
+    #[inline]
+    pub fn from_bits(bits: u32) -> Self {
+        match bits & 0b11 {
+            0 => ContextMode::Lsb6,
+            1 => ContextMode::Msb6,
+            2 => ContextMode::Utf8,
+            _ => ContextMode::Signed,
+        }
+    }
+
+    /// Computes the literal context ID from the previous two bytes of output.
+    ///
+    /// This implements the context functions of RFC 7932 section 7.1.
+    ///
+    /// # Arguments
+    ///
+    /// * `p1` - The last byte of output, or 0 at the start of the stream.
+    /// * `p2` - The byte before `p1`, or 0 if there is none.
+    ///
+    /// # Returns
+    ///
+    /// * The context ID, in 0..64.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::ContextMode;
+    ///
+    /// assert_eq!(ContextMode::Lsb6.context_id(0xff, 0), 0x3f);
+    /// assert_eq!(ContextMode::Msb6.context_id(0xff, 0), 0x3f);
+    ///
+    /// // UTF8: a lowercase letter after a space, an uppercase vowel after a digit, a
+    /// // continuation byte after a lead byte, and a digit after a lowercase letter.
+    /// assert_eq!(ContextMode::Utf8.context_id(b'x', b' '), 60);
+    /// assert_eq!(ContextMode::Utf8.context_id(b'E', b'7'), 50);
+    /// assert_eq!(ContextMode::Utf8.context_id(0x80, 0xc3), 2);
+    /// assert_eq!(ContextMode::Utf8.context_id(b'5', b'a'), 47);
+    /// assert_eq!(ContextMode::Utf8.context_id(b'\n', 0), 4);
+    /// assert_eq!(ContextMode::Utf8.context_id(0xc3, 0x80), 3);
+    ///
+    /// // Signed: class 7 for 0xff, class 0 for 0, class 5 for 0xc0.
+    /// assert_eq!(ContextMode::Signed.context_id(0xff, 0), 56);
+    /// assert_eq!(ContextMode::Signed.context_id(0, 0xc0), 5);
+    /// assert_eq!(ContextMode::Signed.context_id(b'a', 0x10), 26);
+    /// ```
+    #[inline]
+    pub fn context_id(self, p1: u8, p2: u8) -> u8 {
+        match self {
+            ContextMode::Lsb6 => p1 & 0x3f,
+            ContextMode::Msb6 => p1 >> 2,
+            ContextMode::Utf8 => UTF8_CONTEXT_P1[p1 as usize] | UTF8_CONTEXT_P2[p2 as usize],
+            ContextMode::Signed => (SIGNED_CONTEXT[p1 as usize] << 3) | SIGNED_CONTEXT[p2 as usize],
+        }
+    }
+}
+
+/// Contribution of the last byte to a UTF8 context ID (Lut0 in RFC 7932 section 7.1).
+#[rustfmt::skip]
+const UTF8_CONTEXT_P1: [u8; 256] = [
+     0,  0,  0,  0,  0,  0,  0,  0,  0,  4,  4,  0,  0,  4,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,
+     8, 12, 16, 12, 12, 20, 12, 16, 24, 28, 12, 12, 32, 12, 36, 12,
+    44, 44, 44, 44, 44, 44, 44, 44, 44, 44, 32, 32, 24, 40, 28, 12,
+    12, 48, 52, 52, 52, 48, 52, 52, 52, 48, 52, 52, 52, 52, 52, 48,
+    52, 52, 52, 52, 52, 48, 52, 52, 52, 52, 52, 24, 12, 28, 12, 12,
+    12, 56, 60, 60, 60, 56, 60, 60, 60, 56, 60, 60, 60, 60, 60, 56,
+    60, 60, 60, 60, 60, 56, 60, 60, 60, 60, 60, 24, 12, 28, 12,  0,
+     0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,
+     0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,
+     0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,
+     0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,  0,  1,
+     2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,
+     2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,
+     2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,
+     2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,  2,  3,
+];
+
+/// Contribution of the byte before the last to a UTF8 context ID (Lut1 in RFC 7932
+/// section 7.1).
+#[rustfmt::skip]
+const UTF8_CONTEXT_P2: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1,
+    1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1,
+    1, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 1, 1, 1, 1, 0,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+];
+
+/// Signedness class of a byte for the Signed context mode (Lut2 in RFC 7932 section 7.1).
+#[rustfmt::skip]
+const SIGNED_CONTEXT: [u8; 256] = [
+    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+    5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5,
+    5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5,
+    5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5,
+    6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 7,
+];
+
+/// Read the 2-bit context mode for each literal block type.
+///
+/// # Arguments
+///
+/// * `reader` - A mutable reference to a `BitReader` instance.
+/// * `num_types` - The number of literal block types (NBLTYPESL).
+///
+/// # Returns
+///
+/// * A Result containing one `ContextMode` per literal block type, in block type order.
+///
+/// # Errors
+///
+/// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{BitReader, ContextMode};
+/// use brotschneider::context::read_context_modes;
+///
+/// let data = [0b10_00_11_01];
+/// let mut reader = BitReader::new(&data);
+/// let modes = read_context_modes(&mut reader, 4).unwrap();
+///
+/// assert_eq!(
+///     modes,
+///     vec![
+///         ContextMode::Utf8,
+///         ContextMode::Lsb6,
+///         ContextMode::Signed,
+///         ContextMode::Msb6,
+///     ]
+/// );
+/// ```
+pub fn read_context_modes(
+    reader: &mut BitReader,
+    num_types: usize,
+) -> Result<Vec<ContextMode>, BitReaderError> {
+    let mut modes = Vec::with_capacity(num_types);
+    for _ in 0..num_types {
+        modes.push(ContextMode::from_bits(reader.read_bits(2)?));
+    }
+
+    Ok(modes)
+}
+
+/// Apply the inverse move-to-front transform to a decoded context map, in place.
+///
+/// Context maps may be stored move-to-front encoded (IMTF bit set). Each value is an index
+/// into a list of the byte values `0..=255`, and the value found there is moved to the
+/// front of the list before the next one is looked up. The literal and distance context
+/// maps share this transform.
+///
+/// # Arguments
+///
+/// * `data` - The move-to-front coded values, replaced by the original values.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::context::inverse_move_to_front;
+///
+/// let mut data = [0, 0, 0];
+/// inverse_move_to_front(&mut data);
+/// assert_eq!(data, [0, 0, 0]);
+///
+/// // Index 1 is value 1, which moves to the front; then it is at index 0, and value 0 at
+/// // index 1.
+/// let mut data = [1, 0, 1];
+/// inverse_move_to_front(&mut data);
+/// assert_eq!(data, [1, 1, 0]);
+///
+/// let mut data = [2, 2, 2, 255, 0, 1];
+/// inverse_move_to_front(&mut data);
+/// assert_eq!(data, [2, 1, 0, 255, 255, 0]);
+/// ```
+///
+/// It undoes the forward transform:
+///
+/// ```rust
+/// use brotschneider::context::inverse_move_to_front;
+///
+/// let original: Vec<u8> = (0..500u32).map(|i| (i * i % 7 + i % 3 * 40) as u8).collect();
+///
+/// let mut list: Vec<u8> = (0..=255).collect();
+/// let mut data: Vec<u8> = original
+///     .iter()
+///     .map(|&value| {
+///         let index = list.iter().position(|&v| v == value).unwrap();
+///         list.remove(index);
+///         list.insert(0, value);
+///         index as u8
+///     })
+///     .collect();
+///
+/// inverse_move_to_front(&mut data);
+/// assert_eq!(data, original);
+/// ```
+pub fn inverse_move_to_front(data: &mut [u8]) {
+    let mut list: [u8; 256] = std::array::from_fn(|i| i as u8);
+
+    for value in data {
+        let index = *value as usize;
+        let front = list[index];
+        *value = front;
+
+        if index != 0 {
+            list.copy_within(..index, 1);
+            list[0] = front;
+        }
+    }
+}
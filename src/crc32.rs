@@ -0,0 +1,112 @@
+/// The reflected CRC-32 polynomial of IEEE 802.3, as used by zlib, gzip and PNG.
+const POLYNOMIAL: u32 = 0xedb8_8320;
+
+/// The CRC of each byte value, for processing a byte at a time.
+const TABLE: [u32; 256] = make_table();
+
+const fn make_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// Crc32 computes the CRC-32 checksum of data supplied in pieces.
+///
+/// This is the CRC-32 of IEEE 802.3, zlib and gzip, so the result can be compared with
+/// checksums stored by those formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32 {
+    /// The running CRC, before the final inversion.
+    state: u32,
+}
+
+impl Crc32 {
+    /// Creates a new Crc32 over no data.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of Crc32.
+    #[inline]
+    pub fn new() -> Self {
+        Self { state: !0 }
+    }
+
+    /// Adds bytes to the checksum.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes following those already added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Crc32;
+    ///
+    /// let mut crc = Crc32::new();
+    /// crc.update(b"1234");
+    /// crc.update(b"56789");
+    /// assert_eq!(crc.finish(), 0xcbf4_3926);
+    /// ```
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) {
+        let mut state = self.state;
+        for &byte in bytes {
+            state = TABLE[((state ^ byte as u32) & 0xff) as usize] ^ (state >> 8);
+        }
+        self.state = state;
+    }
+
+    /// Returns the checksum of the bytes added so far.
+    ///
+    /// More bytes may still be added afterwards.
+    #[inline]
+    pub fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the CRC-32 checksum of `bytes` in one go.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to checksum.
+///
+/// # Returns
+///
+/// * The checksum.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::crc32::crc32;
+///
+/// assert_eq!(crc32(b""), 0);
+/// assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+/// assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414f_a339);
+/// ```
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(bytes);
+    crc.finish()
+}
@@ -0,0 +1,2473 @@
+use crate::command::DistanceSource;
+use crate::error::{BitReaderError, MetaBlockError};
+use crate::metablock::{DEFAULT_MAX_TREES_PER_META_BLOCK, MAX_TREES_PER_META_BLOCK};
+use crate::outline::{MetaBlockOutline, StreamOutline};
+use crate::{
+    BitReader, Command, Crc32, DecodeContext, DecodeFailure, DecodeState, Decompressed, MetaBlock,
+    MetaBlockHeader, MetaBlockSummary, Recovered,
+};
+
+/// A callback given the bit offset, the output offset and each decoded meta-block.
+type OnMetaBlock<'a> = &'a mut dyn FnMut(usize, usize, &MetaBlock);
+
+/// Decompressor decodes a complete Brotli stream into a byte vector.
+#[derive(Debug, Clone, Default)]
+pub struct Decompressor {
+    /// Whether to reject streams with non-zero padding bits after the last meta-block.
+    strict: bool,
+    /// The maximum number of decompressed bytes, if limited.
+    max_output_len: Option<usize>,
+    /// The maximum ratio of decompressed to compressed bytes, if limited.
+    ratio_limit: Option<u32>,
+    /// A custom dictionary that backward references may reach into before the output start.
+    dictionary: Vec<u8>,
+    /// Whether to bulk-copy literals coded with an identity literal tree.
+    fast_literals: bool,
+    /// Whether to skip copies and output only the inserted literals.
+    literals_only: bool,
+    /// The maximum number of prefix codes per meta-block, if not the default.
+    max_trees_per_block: Option<usize>,
+    /// Bytes that the next stream's backward references may reach, set by `prime_window`.
+    primed: Vec<u8>,
+    /// The state of the current stream. Its window holds the dictionary, then any primed
+    /// bytes, followed by the output.
+    context: DecodeContext,
+    /// Whether the last meta-block of the current stream has been decoded.
+    finished: bool,
+    /// The window size exponent read from the header of the current stream.
+    window_bits: Option<u8>,
+    /// The position in the window where the output of the current stream starts.
+    output_start: usize,
+    /// The position in the window of the first output byte not yet consumed.
+    output_pos: usize,
+    /// The number of bytes the current stream has produced, including consumed ones that
+    /// have been dropped from the window.
+    produced: usize,
+    /// The meta-block being decoded by `step`, if one is in progress.
+    block: Option<MetaBlock>,
+    /// The number of input bits consumed and the total input length in bits, as of the
+    /// last meta-block or command decoded.
+    input_bits: Option<(usize, usize)>,
+}
+
+impl Decompressor {
+    /// Creates a new Decompressor with default options.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of Decompressor.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// let decompressor = Decompressor::new();
+    /// assert!(!decompressor.is_strict());
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            strict: false,
+            max_output_len: None,
+            ratio_limit: None,
+            dictionary: Vec::new(),
+            fast_literals: false,
+            literals_only: false,
+            max_trees_per_block: None,
+            primed: Vec::new(),
+            context: DecodeContext::new(),
+            finished: false,
+            window_bits: None,
+            output_start: 0,
+            output_pos: 0,
+            produced: 0,
+            block: None,
+            input_bits: None,
+        }
+    }
+
+    /// Creates a builder for configuring a Decompressor.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of DecompressorBuilder with default options.
+    #[inline]
+    pub fn builder() -> DecompressorBuilder {
+        DecompressorBuilder::new()
+    }
+
+    /// Enables or disables strict mode.
+    ///
+    /// In strict mode, any unused bits left in the final byte after the last meta-block
+    /// must be zero, as required by RFC 7932. Non-strict mode ignores them for compatibility
+    /// with encoders that leave garbage in the padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether to enable strict mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// decompressor.set_strict(true);
+    /// assert!(decompressor.is_strict());
+    /// ```
+    #[inline]
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns whether strict mode is enabled.
+    #[inline]
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Clear the state of the last decoded stream, keeping its allocations for reuse.
+    ///
+    /// Every decode starts by resetting the decompressor, so this is only needed to release
+    /// the previous output early. Options and cross-stream state such as the custom dictionary
+    /// are preserved; change them through the setters or a new builder.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = Decompressor::builder().strict(true).build();
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"abab");
+    ///
+    /// decompressor.reset();
+    /// assert!(decompressor.is_strict());
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"abab");
+    /// ```
+    #[inline]
+    pub fn reset(&mut self) {
+        self.context.reset();
+        self.finished = false;
+        self.window_bits = None;
+        self.output_start = 0;
+        self.output_pos = 0;
+        self.produced = 0;
+        self.block = None;
+        self.input_bits = None;
+    }
+
+    /// Seeds the history of the next stream with bytes from a prior decode.
+    ///
+    /// Backward references of the next stream may reach into `bytes` as if they had been
+    /// decoded immediately before it, which lets a sequence of independent streams share
+    /// context, such as the tail of the previous chunk. Unlike the custom dictionary, which
+    /// precedes every stream, the primed bytes are used by the next decode only. When both
+    /// are set, the primed bytes follow the dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The history to seed, oldest byte first. Replaces any earlier priming.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor, MetaBlockError};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    /// writer.write_simple_code(&[b'!' as u16], 256).unwrap(); // literals
+    /// writer.write_simple_code(&[130], 704).unwrap(); // insert 0, copy 4
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance, initially 4
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// decompressor.prime_window(b"hello");
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"ello");
+    ///
+    /// // The priming is used up, so the reference now falls before the start of the output.
+    /// assert!(matches!(
+    ///     decompressor.decompress(&stream),
+    ///     Err(MetaBlockError::Unsupported)
+    /// ));
+    /// ```
+    #[inline]
+    pub fn prime_window(&mut self, bytes: &[u8]) {
+        self.primed.clear();
+        self.primed.extend_from_slice(bytes);
+    }
+
+    /// Returns whether the last meta-block of the current stream has been decoded.
+    ///
+    /// This becomes true once a meta-block with ISLAST set, including an empty last
+    /// meta-block, has been decoded in full. A decode that stops early, such as
+    /// `decompress_prefix` reaching its limit, leaves it false.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    ///
+    /// // "abc", then a copy of 2 bytes at distance 3: "abcab".
+    /// writer.write_bits(0b000, 3).unwrap(); // ISLAST = 0, MNIBBLES = 4
+    /// writer.write_bits(4, 16).unwrap(); // MLEN = 5
+    /// writer.write_bits(0, 1).unwrap(); // ISUNCOMPRESSED = 0
+    /// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    /// writer.write_simple_code(&[b'a' as u16, b'b' as u16, b'c' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[152], 704).unwrap(); // insert 3, copy 2
+    /// writer.write_simple_code(&[4], 64).unwrap(); // last distance - 1 = 3
+    /// writer.write_bits(0b0_10_11, 5).unwrap(); // literals "abc"
+    ///
+    /// writer.write_bits(0b11, 2).unwrap(); // ISLAST, ISLASTEMPTY
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert!(!decompressor.is_finished());
+    ///
+    /// // Stopping after the first meta-block leaves the stream unfinished.
+    /// assert_eq!(decompressor.decompress_prefix(&stream, 5).unwrap(), b"abcab");
+    /// assert!(!decompressor.is_finished());
+    ///
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"abcab");
+    /// assert!(decompressor.is_finished());
+    /// ```
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns the window size exponent (WBITS) of the current stream.
+    ///
+    /// The value is known as soon as the stream header has been read, so it is available
+    /// after a partial decode or a decode that failed in a later meta-block.
+    ///
+    /// # Returns
+    ///
+    /// * The WBITS value, from 10 to 24, or `None` if no stream header has been read since
+    ///   the last reset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// // WBITS = 22, followed by an empty last meta-block.
+    /// let stream = [0b1101_1100];
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert_eq!(decompressor.window_bits(), None);
+    ///
+    /// decompressor.decompress(&stream).unwrap();
+    /// assert_eq!(decompressor.window_bits(), Some(22));
+    /// assert_eq!(decompressor.window_size(), Some((1 << 22) - 16));
+    ///
+    /// decompressor.reset();
+    /// assert_eq!(decompressor.window_size(), None);
+    /// ```
+    #[inline]
+    pub fn window_bits(&self) -> Option<u8> {
+        self.window_bits
+    }
+
+    /// Returns the size of the sliding window of the current stream, in bytes.
+    ///
+    /// This is the furthest a backward reference may reach into the output, and so the
+    /// amount of output a streaming consumer has to keep: `(1 << WBITS) - 16`.
+    ///
+    /// # Returns
+    ///
+    /// * The window size, or `None` if no stream header has been read since the last reset.
+    #[inline]
+    pub fn window_size(&self) -> Option<usize> {
+        self.window_bits.map(|window_bits| (1 << window_bits) - 16)
+    }
+
+    /// Decompress a complete Brotli stream, writing the output to `out` as it is decoded.
+    ///
+    /// Each meta-block is written as soon as it has been decoded, and only the bytes that
+    /// backward references can still reach are kept, so memory use is bounded by the window
+    /// size and the largest meta-block rather than by the size of the output.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    /// * `out` - The writer to send the decompressed bytes to.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::Io` if writing to `out` fails.
+    /// * Returns the same errors as `Decompressor::decompress` otherwise. Bytes decoded
+    ///   before an error have already been written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::metablock::write_uncompressed_block;
+    /// use brotschneider::{BitWriter, Decompressor, decompress};
+    ///
+    /// // 20 KiB in meta-blocks of 1000 bytes, with a window of 1008 bytes.
+    /// let data: Vec<u8> = (0..20 * 1024).map(|i| (i % 253) as u8).collect();
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b100_0010, 7).unwrap(); // WBITS = 10
+    /// for chunk in data.chunks(1000) {
+    ///     write_uncompressed_block(&mut writer, chunk, false).unwrap();
+    /// }
+    /// writer.write_bits(0b11, 2).unwrap(); // ISLAST, ISLASTEMPTY
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let mut out = Vec::new();
+    /// let written = decompressor.decompress_to_writer(&stream, &mut out).unwrap();
+    ///
+    /// assert_eq!(written, data.len() as u64);
+    /// assert_eq!(decompress(&stream).unwrap(), out);
+    /// assert!(decompressor.is_finished());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decompress_to_writer<W: std::io::Write>(
+        &mut self,
+        input: &[u8],
+        out: &mut W,
+    ) -> Result<u64, MetaBlockError> {
+        self.reset();
+
+        if input.is_empty() {
+            return Err(MetaBlockError::EmptyInput);
+        }
+
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+        self.window_bits = Some(read_window_bits(&mut reader)?);
+        self.open_window(0);
+
+        let mut written = 0u64;
+        loop {
+            // Decode one byte past the limit so that exceeding it can be detected.
+            let produced = usize::try_from(written).unwrap_or(usize::MAX);
+            self.context.max_len = limit.saturating_add(1).saturating_sub(produced);
+
+            let block = MetaBlock::decode_with_context(&mut reader, &mut self.context)?;
+            self.track_input(&reader);
+
+            written += block.data.len() as u64;
+            if written > limit as u64 {
+                return Err(self.limit_error(limit));
+            }
+
+            out.write_all(&block.data)?;
+            // Written bytes are only kept while backward references can reach them.
+            self.consume(block.data.len());
+
+            if block.header.is_last {
+                break;
+            }
+        }
+
+        if self.strict {
+            check_padding(&mut reader)?;
+        } else {
+            reader.align_to_byte();
+        }
+        self.finished = true;
+        self.track_input(&reader);
+
+        Ok(written)
+    }
+
+    /// Decompress a complete Brotli stream and compute the CRC-32 of the output.
+    ///
+    /// Brotli streams carry no checksum, so this is for verifying the output against one
+    /// stored elsewhere. The CRC is updated as the bytes are written to the output, without
+    /// a second pass over it.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decompressed bytes and their CRC-32, as computed by
+    ///   `crc32::crc32`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `Decompressor::decompress`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    /// use brotschneider::crc32::crc32;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let (output, crc) = decompressor.decompress_with_crc(&stream).unwrap();
+    /// assert_eq!(output, b"abab");
+    /// assert_eq!(crc, crc32(b"abab"));
+    /// ```
+    pub fn decompress_with_crc(
+        &mut self,
+        input: &[u8],
+    ) -> Result<(Decompressed, u32), MetaBlockError> {
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+
+        // Decoding restarts the CRC, and it is removed again even if decoding fails.
+        self.context.crc = Some(Crc32::new());
+        let result = self.decode_to_window(&mut reader, limit, usize::MAX, 0, None, true);
+        let crc = self.context.crc.take().unwrap_or_default();
+        let (start, meta_blocks) = result?;
+        let output = Decompressed::new(self.context.window[start..].to_vec(), meta_blocks);
+
+        Ok((output, crc.finish()))
+    }
+
+    /// Decode the next command of a stream and apply it.
+    ///
+    /// The stream is read from `reader`, starting with its WBITS header on the first call
+    /// after a reset; later calls must pass a reader at the position the previous call left
+    /// it. Meta-block headers, prefix codes and metadata are read as needed along the way.
+    /// An uncompressed meta-block is reported as a single command that inserts its bytes.
+    ///
+    /// The output of a meta-block is added to `output` once its last command has been
+    /// applied. The output limits are checked at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The compressed stream, positioned where the previous call stopped.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the command that was applied, or `None` once the last
+    ///   meta-block of the stream has been decoded.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `Decompressor::decompress`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::command::{Command, DistanceSource};
+    /// use brotschneider::{BitReader, BitWriter, Decompressor, metablock::write_uncompressed_block};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bit(false).unwrap(); // WBITS = 16
+    /// write_uncompressed_block(&mut writer, b"abcd", false).unwrap();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(11, 16).unwrap(); // MLEN = 12
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'x' as u16, b'y' as u16], 256).unwrap();
+    /// // Insert 2, copy 2 with the implicit distance; insert 3, copy 2.
+    /// writer.write_simple_code(&[16, 152], 704).unwrap();
+    /// writer.write_simple_code(&[4], 64).unwrap(); // last distance - 1
+    /// writer.write_bits(0b0_01, 3).unwrap(); // implicit command, literals "xy"
+    /// writer.write_bits(0b1_010, 4).unwrap(); // explicit command, literals "xyx"
+    /// writer.write_bits(0b1_010, 4).unwrap(); // explicit command, literals "xyx"
+    /// let stream = writer.into_inner();
+    ///
+    /// let command = |insert_len, copy_len, distance| Command {
+    ///     insert_len,
+    ///     copy_len,
+    ///     distance,
+    ///     source: DistanceSource::Ring(0),
+    /// };
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let mut reader = BitReader::new(&stream);
+    ///
+    /// assert_eq!(decompressor.step(&mut reader).unwrap(), Some(command(4, 0, 4)));
+    /// assert_eq!(decompressor.output(), b"abcd");
+    ///
+    /// assert_eq!(decompressor.step(&mut reader).unwrap(), Some(command(2, 2, 4)));
+    /// assert_eq!(decompressor.step(&mut reader).unwrap(), Some(command(3, 2, 3)));
+    /// assert_eq!(decompressor.output(), b"abcd");
+    ///
+    /// // The literals of the last command complete the meta-block, so its copy is ignored.
+    /// assert_eq!(decompressor.step(&mut reader).unwrap(), Some(command(3, 0, 3)));
+    /// assert_eq!(decompressor.output(), b"abcdxycdxyxxyxyx");
+    ///
+    /// assert_eq!(decompressor.step(&mut reader).unwrap(), None);
+    /// assert!(decompressor.is_finished());
+    /// assert!(reader.is_empty());
+    /// ```
+    pub fn step(&mut self, reader: &mut BitReader) -> Result<Option<Command>, MetaBlockError> {
+        let result = self.step_command(reader);
+        self.track_input(reader);
+
+        result
+    }
+
+    /// Decode and apply the next command, as described for `step`.
+    fn step_command(&mut self, reader: &mut BitReader) -> Result<Option<Command>, MetaBlockError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let input_len = (reader.bit_offset() + reader.bits_remaining()) / 8;
+        let limit = self.output_limit(input_len);
+
+        if self.window_bits.is_none() {
+            if reader.remaining_slice().is_empty() {
+                return Err(MetaBlockError::EmptyInput);
+            }
+
+            self.window_bits = Some(read_window_bits(reader)?);
+            self.open_window(0);
+        }
+
+        loop {
+            let mut block = match self.block.take() {
+                Some(block) => block,
+                None => {
+                    // Decode one byte past the limit so that exceeding it can be detected.
+                    self.context.max_len = limit.saturating_add(1) - self.produced;
+
+                    let mut block = MetaBlock::begin(reader, &self.context)?;
+                    block.commands = Some(Vec::new());
+                    block
+                }
+            };
+
+            let mut state = block.state();
+            while state != DecodeState::Done {
+                state = block.step(reader, &mut self.context)?;
+                if state == DecodeState::ReadCommand {
+                    break;
+                }
+            }
+
+            let mut command = block.commands.as_mut().and_then(Vec::pop);
+            if block.header.is_uncompressed {
+                command = Some(Command {
+                    insert_len: block.data.len() as u32,
+                    copy_len: 0,
+                    distance: self.context.ring.last(0),
+                    source: DistanceSource::Ring(0),
+                });
+            }
+
+            if state == DecodeState::ReadCommand {
+                self.block = Some(block);
+                return Ok(command);
+            }
+
+            // Consumed bytes may have left the window, so the output is counted separately.
+            self.produced += block.data.len();
+            if self.produced > limit {
+                return Err(self.limit_error(limit));
+            }
+
+            if block.header.is_last {
+                if self.strict {
+                    check_padding(reader)?;
+                } else {
+                    reader.align_to_byte();
+                }
+                self.finished = true;
+            }
+
+            if command.is_some() || self.finished {
+                return Ok(command);
+            }
+        }
+    }
+
+    /// Returns the fraction of the input consumed so far, from 0.0 to 1.0.
+    ///
+    /// The estimate is the position of the reader after the last meta-block, or the last
+    /// command when decoding with `step`, over the length of the input. It is most useful
+    /// with `step`, whose caller can report progress between calls.
+    ///
+    /// # Returns
+    ///
+    /// * The fraction, or `None` if no input has been decoded since the last reset or the
+    ///   input is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, Decompressor};
+    /// use brotschneider::metablock::write_uncompressed_block;
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bit(false).unwrap(); // WBITS = 16
+    /// write_uncompressed_block(&mut writer, &[b'a'; 100], false).unwrap();
+    /// write_uncompressed_block(&mut writer, &[b'b'; 300], true).unwrap();
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert_eq!(decompressor.progress(), None);
+    ///
+    /// let mut reader = BitReader::new(&stream);
+    /// let mut progress = Vec::new();
+    /// while decompressor.step(&mut reader).unwrap().is_some() {
+    ///     progress.push(decompressor.progress().unwrap());
+    /// }
+    ///
+    /// // The first meta-block is a quarter of the stream.
+    /// assert!(progress[0] > 0.2 && progress[0] < 0.3);
+    ///
+    /// // The empty last meta-block is read by the call that returns `None`.
+    /// assert!(progress[1] > 0.99 && progress[1] < 1.0);
+    /// assert_eq!(decompressor.progress(), Some(1.0));
+    ///
+    /// decompressor.reset();
+    /// assert_eq!(decompressor.progress(), None);
+    /// ```
+    pub fn progress(&self) -> Option<f32> {
+        match self.input_bits {
+            Some((consumed, total)) if total > 0 => Some((consumed as f64 / total as f64) as f32),
+            _ => None,
+        }
+    }
+
+    /// Record the position of `reader` for `progress`.
+    #[inline]
+    fn track_input(&mut self, reader: &BitReader) {
+        let consumed = reader.bit_offset();
+        self.input_bits = Some((consumed, consumed + reader.bits_remaining()));
+    }
+
+    /// Returns the decoded bytes of the current stream that have not been consumed yet.
+    ///
+    /// The bytes are borrowed from the decoder's window, so they can be processed without
+    /// copying them out. Call `consume` to mark bytes as handled.
+    ///
+    /// # Returns
+    ///
+    /// * The output produced since the last decode started, minus the consumed bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert_eq!(decompressor.output(), b"");
+    ///
+    /// decompressor.decompress(&stream).unwrap();
+    /// assert_eq!(decompressor.output(), b"abab");
+    ///
+    /// decompressor.consume(3);
+    /// assert_eq!(decompressor.output(), b"b");
+    ///
+    /// // Consuming more than is available consumes everything.
+    /// decompressor.consume(10);
+    /// assert_eq!(decompressor.output(), b"");
+    ///
+    /// // A new decode starts a new output.
+    /// decompressor.decompress_prefix(&stream, 2).unwrap();
+    /// assert_eq!(decompressor.output(), b"ab");
+    /// ```
+    #[inline]
+    pub fn output(&self) -> &[u8] {
+        &self.context.window[self.output_pos..]
+    }
+
+    /// Marks the first `n` bytes of `output` as handled.
+    ///
+    /// Consumed bytes that lie further back than the window size of the stream can no longer
+    /// be reached by backward references, so their memory is reclaimed. Consumed bytes
+    /// inside the window, and the dictionary and primed bytes before the output, are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of bytes to consume. It is capped at the length of `output`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor, metablock::write_uncompressed_block};
+    ///
+    /// let data: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b100_0010, 7).unwrap(); // WBITS = 10
+    /// write_uncompressed_block(&mut writer, &data, true).unwrap();
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// decompressor.decompress(&stream).unwrap();
+    /// let window_size = decompressor.window_size().unwrap();
+    /// assert_eq!(window_size, 1008);
+    ///
+    /// // Consume in steps around the window size, so that bytes are reclaimed both just
+    /// // inside and just outside of the window.
+    /// let mut consumed = 0;
+    /// for step in [window_size - 1, 1, 1, window_size + 1, 0, 2 * window_size] {
+    ///     decompressor.consume(step);
+    ///     consumed = (consumed + step).min(data.len());
+    ///     assert_eq!(decompressor.output(), &data[consumed..]);
+    /// }
+    /// ```
+    ///
+    /// Consumed bytes still count towards the output limits:
+    ///
+    /// ```rust
+    /// use brotschneider::metablock::write_uncompressed_block;
+    /// use brotschneider::{BitReader, BitWriter, Decompressor, MetaBlockError};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b100_0010, 7).unwrap(); // WBITS = 10
+    /// for i in 0..10 {
+    ///     write_uncompressed_block(&mut writer, &[b'a'; 1000], i == 9).unwrap();
+    /// }
+    /// let stream = writer.into_inner();
+    ///
+    /// // Consuming keeps the window well under 5000 bytes, but the output passes them.
+    /// let mut decompressor = Decompressor::builder().max_output_len(5000).build();
+    /// let mut reader = BitReader::new(&stream);
+    /// let result = loop {
+    ///     match decompressor.step(&mut reader) {
+    ///         Ok(Some(_)) => decompressor.consume(decompressor.output().len()),
+    ///         result => break result,
+    ///     }
+    /// };
+    /// assert!(matches!(result, Err(MetaBlockError::OutputTooLarge)));
+    /// ```
+    pub fn consume(&mut self, n: usize) {
+        self.output_pos += n.min(self.context.window.len() - self.output_pos);
+        self.reclaim();
+    }
+
+    /// Drop consumed output that backward references can no longer reach from the window.
+    fn reclaim(&mut self) {
+        let window_size = self.window_size();
+        let window = &mut self.context.window;
+
+        let Some(window_size) = window_size else {
+            return;
+        };
+
+        // Only consumed output may be dropped, never the dictionary or primed bytes.
+        let output_start = self.output_start;
+        let reachable = window.len().saturating_sub(window_size);
+        let droppable = self.output_pos.min(reachable).saturating_sub(output_start);
+
+        // Dropping shifts the rest of the window, so it is only done once at least as many
+        // bytes can be dropped as are kept, which bounds the copying by the bytes consumed.
+        if droppable > 0 && droppable >= window.len() - droppable {
+            window.drain(output_start..output_start + droppable);
+            self.output_pos -= droppable;
+        }
+    }
+
+    /// Decompress a complete Brotli stream.
+    ///
+    /// The input is read in place and never copied, so it may be a slice over a
+    /// memory-mapped file; the mapping only has to stay alive for the duration of the call.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decompressed bytes, or an error if decoding fails.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::EmptyInput` if `input` is empty.
+    /// * Returns `MetaBlockError::InvalidWindowBits` if the stream header is invalid.
+    /// * Returns `MetaBlockError::NonZeroPadding` in strict mode if the padding bits after the
+    ///   last meta-block are not zero.
+    /// * Returns `MetaBlockError::OutputTooLarge` if the output exceeds `max_output_len`.
+    /// * Returns `MetaBlockError::RatioLimitExceeded` if the output exceeds `ratio_limit` times
+    ///   the input length.
+    /// * Returns any error produced while decoding a meta-block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor, MetaBlockError};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(0, 16).unwrap(); // MLEN = 1
+    /// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    /// writer.write_simple_code(&[b'x' as u16], 256).unwrap(); // literals
+    /// writer.write_simple_code(&[8], 704).unwrap(); // insert 1, copy 2
+    /// writer.write_simple_code(&[0], 64).unwrap(); // distances
+    /// writer.write_bits(0b11, 2).unwrap(); // non-zero padding
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"x");
+    ///
+    /// decompressor.set_strict(true);
+    /// assert!(matches!(
+    ///     decompressor.decompress(&stream),
+    ///     Err(MetaBlockError::NonZeroPadding)
+    /// ));
+    /// ```
+    ///
+    /// The output window and the distance ring carry over from one meta-block to the next:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    ///
+    /// // "abc", then a copy of 2 bytes at distance 3: "abcab".
+    /// writer.write_bits(0b000, 3).unwrap(); // ISLAST = 0, MNIBBLES = 4
+    /// writer.write_bits(4, 16).unwrap(); // MLEN = 5
+    /// writer.write_bits(0, 1).unwrap(); // ISUNCOMPRESSED = 0
+    /// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    /// writer.write_simple_code(&[b'a' as u16, b'b' as u16, b'c' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[152], 704).unwrap(); // insert 3, copy 2
+    /// writer.write_simple_code(&[4], 64).unwrap(); // last distance - 1 = 3
+    /// writer.write_bits(0b0_10_11, 5).unwrap(); // literals "abc"
+    ///
+    /// // A copy of 4 bytes at the last distance, which is still 3: "cabc".
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'!' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[130], 704).unwrap(); // insert 0, copy 4
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"abcabcabc");
+    /// ```
+    #[inline]
+    pub fn decompress(&mut self, input: &[u8]) -> Result<Decompressed, MetaBlockError> {
+        self.decode_stream(input, usize::MAX, 0)
+    }
+
+    /// Decompress a complete Brotli stream, reserving room for `expected_output` bytes.
+    ///
+    /// The reservation is capped at `max_output_len` and the `ratio_limit` bound, so an
+    /// untrusted hint cannot trigger an allocation larger than the output would be allowed
+    /// to grow anyway.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    /// * `expected_output` - The expected decompressed length, such as a declared
+    ///   content length.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decompressed bytes, or an error if decoding fails.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `Decompressor::decompress`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// // The hint is clamped to the 16-byte limit instead of reserving a gigabyte.
+    /// let mut decompressor = Decompressor::builder().max_output_len(16).build();
+    /// let output = decompressor.decompress_with_capacity(&stream, 1 << 30).unwrap();
+    /// assert_eq!(output, b"abab");
+    /// ```
+    #[inline]
+    pub fn decompress_with_capacity(
+        &mut self,
+        input: &[u8],
+        expected_output: usize,
+    ) -> Result<Decompressed, MetaBlockError> {
+        self.decode_stream(input, usize::MAX, expected_output)
+    }
+
+    /// Decompress a complete Brotli stream into a caller-provided buffer.
+    ///
+    /// This suits callers that know the output size in advance, such as from a declared
+    /// content length. The output is written straight into `out`, with no buffer in between,
+    /// and backward references read from the part of `out` already written.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    /// * `out` - The buffer to write the decompressed bytes into.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the number of bytes written to the front of `out`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::OutputTooLarge` if the output does not fit in `out`; its
+    ///   contents are unspecified in that case.
+    /// * Returns the same errors as `Decompressor::decompress`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{Decompressor, MetaBlockError};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let mut out = [0u8; 8];
+    /// let written = decompressor.decompress_into(&stream, &mut out).unwrap();
+    /// assert_eq!(&out[..written], b"abab");
+    ///
+    /// let mut out = [0u8; 3];
+    /// assert!(matches!(
+    ///     decompressor.decompress_into(&stream, &mut out),
+    ///     Err(MetaBlockError::OutputTooLarge)
+    /// ));
+    /// ```
+    ///
+    /// Copies reach back into the output of earlier meta-blocks and into the dictionary:
+    ///
+    /// ```rust
+    /// use brotschneider::metablock::write_uncompressed_block;
+    /// use brotschneider::{BitWriter, Decompressor, MetaBlockHeader};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    /// write_uncompressed_block(&mut writer, b"xyz", false).unwrap();
+    /// MetaBlockHeader {
+    ///     is_last: true,
+    ///     is_last_empty: false,
+    ///     length: 8,
+    ///     is_uncompressed: false,
+    ///     is_metadata: false,
+    /// }
+    /// .write(&mut writer)
+    /// .unwrap();
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'!' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[134], 704).unwrap(); // insert 0, copy 8
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance, initially 4
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::builder().dictionary(b"abc".to_vec()).build();
+    /// let mut out = [0u8; 11];
+    /// let written = decompressor.decompress_into(&stream, &mut out).unwrap();
+    /// assert_eq!(&out[..written], b"xyzcxyzcxyz");
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), &out[..written]);
+    /// ```
+    pub fn decompress_into(
+        &mut self,
+        input: &[u8],
+        out: &mut [u8],
+    ) -> Result<usize, MetaBlockError> {
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+
+        self.reset();
+
+        if input.is_empty() {
+            return Err(MetaBlockError::EmptyInput);
+        }
+
+        self.window_bits = Some(read_window_bits(&mut reader)?);
+
+        // The window only holds the dictionary and any primed bytes; the output stays in
+        // `out`.
+        self.open_window(0);
+
+        // Decode one byte past the limit, if `out` has room for it, so that exceeding the
+        // limit can be detected.
+        let end = out.len().min(limit.saturating_add(1));
+        let mut pos = 0;
+
+        loop {
+            self.context.max_len = end - pos;
+            let (block, written) =
+                MetaBlock::decode_into(&mut reader, &mut self.context, out, pos)?;
+            pos += written;
+            self.track_input(&reader);
+
+            if pos > limit {
+                return Err(self.limit_error(limit));
+            }
+
+            // A meta-block cut short by the end of `out` did not fit in it.
+            let header = &block.header;
+            let length = if header.is_metadata || header.is_last_empty {
+                0
+            } else {
+                header.length as usize
+            };
+            if written + block.skipped < length {
+                return Err(MetaBlockError::OutputTooLarge);
+            }
+
+            if header.is_last {
+                break;
+            }
+        }
+
+        if self.strict {
+            check_padding(&mut reader)?;
+        } else {
+            reader.align_to_byte();
+        }
+        self.finished = true;
+        self.track_input(&reader);
+
+        Ok(pos)
+    }
+
+    /// Decompress at most the first `max_bytes` bytes of a Brotli stream.
+    ///
+    /// Decoding stops as soon as `max_bytes` bytes have been produced, even in the middle of
+    /// a meta-block, and the rest of the stream is not validated.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    /// * `max_bytes` - The maximum number of bytes to decompress.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing up to `max_bytes` decompressed bytes, or an error if decoding
+    ///   fails before then.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert_eq!(decompressor.decompress_prefix(&stream, 3).unwrap(), b"aba");
+    /// assert_eq!(decompressor.decompress_prefix(&stream, 10).unwrap(), b"abab");
+    /// ```
+    #[inline]
+    pub fn decompress_prefix(
+        &mut self,
+        input: &[u8],
+        max_bytes: usize,
+    ) -> Result<Decompressed, MetaBlockError> {
+        self.decode_stream(input, max_bytes, 0)
+    }
+
+    /// Decompress every Brotli stream concatenated in `input`.
+    ///
+    /// Each stream has its own WBITS header and starts at the byte boundary following the
+    /// previous stream's last meta-block. Zero bytes after the last stream are taken as
+    /// padding. The output limits apply to the combined output of all streams, so each
+    /// stream may only produce what the streams before it left of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - One or more compressed streams, back to back.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decompressed bytes of each stream, in order.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `Decompressor::decompress` for any of the streams.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{Decompressor, MetaBlockError};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    /// // A stream with a single empty last meta-block.
+    /// let empty = [0b0110_0000];
+    /// let input = [&stream[..], &empty, &stream].concat();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let outputs = decompressor.decompress_all(&input).unwrap();
+    /// assert_eq!(outputs, [&b"abab"[..], b"", b"abab"]);
+    ///
+    /// // Trailing zero bytes are padding, not the start of another stream.
+    /// let input = [&stream[..], &[0, 0]].concat();
+    /// assert_eq!(decompressor.decompress_all(&input).unwrap(), [b"abab"]);
+    ///
+    /// // The second stream exceeds what the first left of the output limit.
+    /// let input = [stream, stream].concat();
+    /// let mut decompressor = Decompressor::builder().max_output_len(6).build();
+    /// assert!(matches!(
+    ///     decompressor.decompress_all(&input),
+    ///     Err(MetaBlockError::OutputTooLarge)
+    /// ));
+    /// ```
+    ///
+    /// The distance ring carries over from one meta-block to the next, but each stream
+    /// starts again from the initial ring:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor, MetaBlockHeader};
+    ///
+    /// let header = |is_last, length| MetaBlockHeader {
+    ///     is_last,
+    ///     is_last_empty: false,
+    ///     length,
+    ///     is_uncompressed: false,
+    ///     is_metadata: false,
+    /// };
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    /// // "xy", then a copy of 2 bytes at the explicit distance 1.
+    /// header(false, 4).write(&mut writer).unwrap();
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'x' as u16, b'y' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[144], 704).unwrap(); // insert 2, copy 2
+    /// writer.write_simple_code(&[16], 64).unwrap(); // distance 1 or 2
+    /// writer.write_bits(0b01, 2).unwrap(); // literals "xy"
+    /// writer.write_bits(0, 1).unwrap(); // distance 1
+    /// // A copy of 3 bytes at the last distance, which the first meta-block set to 1.
+    /// header(true, 3).write(&mut writer).unwrap();
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'x' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[129], 704).unwrap(); // insert 0, copy 3
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance
+    /// let first = writer.into_inner();
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    /// // "xyyx", then a copy of 2 bytes at the last distance, initially 4.
+    /// header(true, 6).write(&mut writer).unwrap();
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'x' as u16, b'y' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[160], 704).unwrap(); // insert 4, copy 2
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance
+    /// writer.write_bits(0b0110, 4).unwrap(); // literals "xyyx"
+    /// let second = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let outputs = decompressor
+    ///     .decompress_all(&[first, second].concat())
+    ///     .unwrap();
+    /// assert_eq!(outputs, [&b"xyyyyyy"[..], b"xyyxxy"]);
+    /// ```
+    pub fn decompress_all(&mut self, input: &[u8]) -> Result<Vec<Decompressed>, MetaBlockError> {
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+        let mut outputs = Vec::new();
+        let mut total = 0usize;
+
+        loop {
+            // Each stream is limited to what is left, and a stream that exceeds it is
+            // reported against the overall limit.
+            let output = self
+                .decode_member(&mut reader, limit - total, usize::MAX, 0)
+                .map_err(|e| match e {
+                    MetaBlockError::OutputTooLarge | MetaBlockError::RatioLimitExceeded => {
+                        self.limit_error(limit)
+                    }
+                    e => e,
+                })?;
+            total += output.len();
+
+            outputs.push(output);
+
+            if reader.remaining_slice().iter().all(|&byte| byte == 0) {
+                return Ok(outputs);
+            }
+        }
+    }
+
+    /// Decode a stream and report the boundaries of its meta-blocks.
+    ///
+    /// This maps the structure of a stream for inspection. The output is decoded but not
+    /// kept: bytes are dropped from the window once backward references can no longer reach
+    /// them, so memory use depends on the window size and the longest meta-block rather than
+    /// on the length of the output.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing a summary of each meta-block, in stream order.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `Decompressor::decompress`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::Decompressor;
+    ///
+    /// // A metadata meta-block skipping 1 byte, then an empty last meta-block.
+    /// let stream = [0b0011_0010, 0b0000_0000, 0xab, 0b1100_0000];
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let summaries = decompressor.summarize_meta_blocks(&stream).unwrap();
+    ///
+    /// assert_eq!(summaries.len(), 2);
+    /// assert!(summaries[0].is_metadata);
+    /// assert_eq!(summaries[0].bit_offset, 1);
+    /// assert_eq!(summaries[0].length, 1);
+    /// assert!(summaries[1].is_last);
+    /// assert_eq!(summaries[1].bit_offset, 24);
+    /// assert_eq!(summaries[1].output_range, 0..0);
+    /// ```
+    ///
+    /// Output ranges count every byte decoded, including those already dropped:
+    ///
+    /// ```rust
+    /// use brotschneider::metablock::write_uncompressed_block;
+    /// use brotschneider::{BitWriter, Decompressor};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b100_0010, 7).unwrap(); // WBITS = 10
+    /// for i in 0..10 {
+    ///     write_uncompressed_block(&mut writer, &[b'a'; 1000], i == 9).unwrap();
+    /// }
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let summaries = decompressor.summarize_meta_blocks(&stream).unwrap();
+    /// assert_eq!(summaries[9].output_range, 9000..10000);
+    /// assert!(decompressor.output().is_empty());
+    /// ```
+    pub fn summarize_meta_blocks(
+        &mut self,
+        input: &[u8],
+    ) -> Result<Vec<MetaBlockSummary>, MetaBlockError> {
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+        let mut summaries = Vec::new();
+
+        let mut summarize = |bit_offset, output_start, block: &MetaBlock| {
+            summaries.push(MetaBlockSummary {
+                bit_offset,
+                length: block.header.length,
+                is_last: block.header.is_last,
+                is_uncompressed: block.header.is_uncompressed,
+                is_metadata: block.header.is_metadata,
+                output_range: output_start..output_start + block.data.len(),
+            });
+        };
+        self.decode_to_window(
+            &mut reader,
+            limit,
+            usize::MAX,
+            0,
+            Some(&mut summarize),
+            false,
+        )?;
+
+        Ok(summaries)
+    }
+
+    /// Decode a stream and outline its structure: the header, block types, prefix codes
+    /// and number of commands of each meta-block.
+    ///
+    /// Like `summarize_meta_blocks`, this is meant for inspecting streams, and the output
+    /// is not returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the outline of the stream.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `Decompressor::decompress`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::metablock::write_uncompressed_block;
+    /// use brotschneider::{BitWriter, Decompressor};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// let outline = decompressor.outline(&stream).unwrap();
+    ///
+    /// assert_eq!(outline.window_bits, 16);
+    /// assert_eq!(outline.meta_blocks.len(), 1);
+    ///
+    /// let meta_block = &outline.meta_blocks[0];
+    /// assert_eq!(meta_block.bit_offset, 1);
+    /// assert_eq!(meta_block.block_types, [1, 1, 1]);
+    /// assert_eq!(meta_block.literal_trees[0].symbols, 2);
+    /// assert_eq!(meta_block.output_range, 0..4);
+    /// assert_eq!(meta_block.commands, 1);
+    ///
+    /// // An uncompressed meta-block carries no prefix codes or commands.
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bit(false).unwrap(); // WBITS = 16
+    /// write_uncompressed_block(&mut writer, b"hi", true).unwrap();
+    /// let stream = writer.into_inner();
+    /// let outline = decompressor.outline(&stream).unwrap();
+    ///
+    /// assert_eq!(outline.meta_blocks.len(), 2);
+    /// assert!(outline.meta_blocks[0].header.is_uncompressed);
+    /// assert!(outline.meta_blocks[0].literal_trees.is_empty());
+    /// assert_eq!(outline.meta_blocks[0].commands, 0);
+    /// assert!(outline.meta_blocks[1].header.is_last_empty);
+    /// ```
+    pub fn outline(&mut self, input: &[u8]) -> Result<StreamOutline, MetaBlockError> {
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+        let mut meta_blocks = Vec::new();
+
+        let mut outline = |bit_offset, output_start, block: &MetaBlock| {
+            meta_blocks.push(MetaBlockOutline::new(bit_offset, output_start, block));
+        };
+        self.decode_to_window(&mut reader, limit, usize::MAX, 0, Some(&mut outline), false)?;
+
+        Ok(StreamOutline {
+            // Any successful decode has read the window size.
+            window_bits: self.window_bits.unwrap_or_default(),
+            meta_blocks,
+        })
+    }
+
+    /// Decompress a possibly damaged stream, skipping meta-blocks that fail to decode.
+    ///
+    /// When a meta-block is corrupt, the failure is recorded and decoding resumes with a new
+    /// meta-block header at each following byte boundary until one decodes. Meta-block
+    /// headers are not byte-aligned in general, so this is a best-effort heuristic meant for
+    /// salvaging data from damaged files. Decoding stops at the last meta-block, or at the
+    /// end of input with the unfinished stretch recorded as a failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the recovered output and the skipped stretches of input.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::EmptyInput` if `input` is empty.
+    /// * Returns `MetaBlockError::InvalidWindowBits` if the stream header is invalid.
+    /// * Returns `MetaBlockError::OutputTooLarge` or `MetaBlockError::RatioLimitExceeded` if
+    ///   the recovered output exceeds the configured limits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor, MetaBlockError};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    ///
+    /// // "hi" in a meta-block that ends exactly at bit 80.
+    /// writer.write_bits(0b000, 3).unwrap(); // ISLAST = 0, MNIBBLES = 4
+    /// writer.write_bits(1, 16).unwrap(); // MLEN = 2
+    /// writer.write_bits(0, 1).unwrap(); // ISUNCOMPRESSED = 0
+    /// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    /// writer.write_simple_code(&[b'h' as u16, b'i' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[16], 704).unwrap(); // insert 2, copy 2
+    /// writer.write_simple_code(&[0], 64).unwrap();
+    /// writer.write_bits(0b01, 2).unwrap(); // literals "hi"
+    ///
+    /// // A damaged meta-block header with the reserved bit set, padded to a byte.
+    /// writer.write_bits(0b0111_0000, 8).unwrap();
+    ///
+    /// // "!!" in the last meta-block.
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(1, 16).unwrap(); // MLEN = 2
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'!' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[16], 704).unwrap();
+    /// writer.write_simple_code(&[0], 64).unwrap();
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert!(decompressor.decompress(&stream).is_err());
+    ///
+    /// let recovered = decompressor.decompress_lenient(&stream).unwrap();
+    /// assert_eq!(recovered.data, b"hi!!");
+    /// assert_eq!(recovered.failures.len(), 1);
+    /// assert_eq!(recovered.failures[0].bit_range, 80..88);
+    /// assert!(matches!(
+    ///     recovered.failures[0].error,
+    ///     MetaBlockError::Corrupt { .. }
+    /// ));
+    /// ```
+    pub fn decompress_lenient(&mut self, input: &[u8]) -> Result<Recovered, MetaBlockError> {
+        self.reset();
+
+        if input.is_empty() {
+            return Err(MetaBlockError::EmptyInput);
+        }
+
+        let mut reader = BitReader::new(input);
+        self.window_bits = Some(read_window_bits(&mut reader)?);
+        let limit = self.output_limit(input.len());
+        let input_bits = input.len() * 8;
+
+        let start = self.open_window(0);
+
+        let mut failures: Vec<DecodeFailure> = Vec::new();
+
+        loop {
+            let block_start = reader.bit_offset();
+            self.context.max_len = limit.saturating_add(1) - self.produced;
+
+            let error = match MetaBlock::decode_with_context(&mut reader, &mut self.context) {
+                Ok(block) => {
+                    self.produced += block.data.len();
+                    if self.produced > limit {
+                        return Err(self.limit_error(limit));
+                    }
+
+                    if block.header.is_last {
+                        self.finished = true;
+                        break;
+                    }
+
+                    continue;
+                }
+                Err(error) => error,
+            };
+
+            // Resume at the first byte boundary after the start of the failed meta-block.
+            let resync = (block_start / 8 + 1) * 8;
+            let recoverable = matches!(
+                error,
+                MetaBlockError::Corrupt { .. }
+                    | MetaBlockError::Huffman(_)
+                    | MetaBlockError::Unsupported
+            );
+            let end = if recoverable && resync < input_bits {
+                resync
+            } else {
+                input_bits
+            };
+
+            // Consecutive failed resync attempts extend the same skipped stretch.
+            match failures.last_mut() {
+                Some(failure) if failure.bit_range.end == block_start => {
+                    failure.bit_range.end = end;
+                }
+                _ => failures.push(DecodeFailure {
+                    bit_range: block_start..end,
+                    error,
+                }),
+            }
+
+            if end == input_bits {
+                break;
+            }
+
+            reader = BitReader::new(input);
+            reader.skip_bits(resync)?;
+        }
+
+        Ok(Recovered {
+            data: self.context.window[start..].to_vec(),
+            failures,
+        })
+    }
+
+    /// Compute the total decompressed size of a stream from its meta-block lengths, giving
+    /// up once it exceeds `max_size`.
+    ///
+    /// The lengths are summed from the meta-block headers, and a meta-block that would take
+    /// the total past `max_size` is rejected as soon as its header is read. Compressed
+    /// meta-blocks within the bound still have to be decoded to find where the next one
+    /// starts, which also validates the stream.
+    ///
+    /// The output is not kept. Like for `summarize_meta_blocks`, it is dropped once
+    /// backward references can no longer reach it, so memory use is about the window size
+    /// of the stream plus the output of one meta-block, which is at most 16 MiB and at most
+    /// `max_size` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The compressed stream.
+    /// * `max_size` - The largest total size to accept.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the sum of MLEN over all meta-blocks.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::OutputTooLarge` if the sum exceeds `max_size`.
+    /// * Returns the same errors as `Decompressor::decompress`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, Decompressor, MetaBlockError, MetaBlockHeader};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = Decompressor::new();
+    /// assert_eq!(decompressor.output_size(&stream, 4).unwrap(), 4);
+    /// assert!(matches!(
+    ///     decompressor.output_size(&stream, 3),
+    ///     Err(MetaBlockError::OutputTooLarge)
+    /// ));
+    ///
+    /// // An uncompressed meta-block claiming 1 MiB is rejected from its header alone.
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bit(false).unwrap(); // WBITS = 16
+    /// MetaBlockHeader {
+    ///     is_last: false,
+    ///     is_last_empty: false,
+    ///     length: 1 << 20,
+    ///     is_uncompressed: true,
+    ///     is_metadata: false,
+    /// }
+    /// .write(&mut writer)
+    /// .unwrap();
+    /// let stream = writer.into_inner();
+    ///
+    /// assert!(matches!(
+    ///     decompressor.output_size(&stream, 1 << 16),
+    ///     Err(MetaBlockError::OutputTooLarge)
+    /// ));
+    /// ```
+    pub fn output_size(&mut self, input: &[u8], max_size: u64) -> Result<u64, MetaBlockError> {
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+        let bound = usize::try_from(max_size).unwrap_or(usize::MAX);
+        let mut size = 0u64;
+
+        let mut add_length = |_, _, block: &MetaBlock| {
+            if !block.header.is_metadata {
+                size += block.header.length as u64;
+            }
+        };
+
+        // The bound is enforced like an output limit, but always reported as such.
+        let result = self.decode_to_window(
+            &mut reader,
+            limit.min(bound),
+            usize::MAX,
+            0,
+            Some(&mut add_length),
+            false,
+        );
+        match result {
+            Err(MetaBlockError::OutputTooLarge | MetaBlockError::RatioLimitExceeded)
+                if bound < limit =>
+            {
+                return Err(MetaBlockError::OutputTooLarge);
+            }
+            result => {
+                result?;
+            }
+        }
+
+        Ok(size)
+    }
+
+    /// Decode a stream, stopping early once `max_len` bytes have been produced.
+    ///
+    /// Room for `capacity` output bytes, capped at the output limit, is reserved up front.
+    fn decode_stream(
+        &mut self,
+        input: &[u8],
+        max_len: usize,
+        capacity: usize,
+    ) -> Result<Decompressed, MetaBlockError> {
+        let mut reader = BitReader::new(input);
+        let limit = self.output_limit(input.len());
+
+        self.decode_member(&mut reader, limit, max_len, capacity)
+    }
+
+    /// Decode one stream starting at the reader's position, leaving the reader at the byte
+    /// boundary after its last meta-block.
+    fn decode_member(
+        &mut self,
+        reader: &mut BitReader,
+        limit: usize,
+        max_len: usize,
+        capacity: usize,
+    ) -> Result<Decompressed, MetaBlockError> {
+        let (start, meta_blocks) =
+            self.decode_to_window(reader, limit, max_len, capacity, None, true)?;
+
+        Ok(Decompressed::new(
+            self.context.window[start..].to_vec(),
+            meta_blocks,
+        ))
+    }
+
+    /// Decode one stream like `decode_member`, leaving the output in the window.
+    ///
+    /// If `on_block` is given, it is called with the bit offset, the output offset and the
+    /// meta-block once each meta-block is decoded, with its commands recorded. Unless
+    /// `keep_output` is set, each meta-block's output is consumed as soon as it is decoded,
+    /// so that the window only holds what backward references can still reach.
+    ///
+    /// Returns the offset of the output in the window and the number of meta-blocks decoded.
+    fn decode_to_window(
+        &mut self,
+        reader: &mut BitReader,
+        limit: usize,
+        max_len: usize,
+        capacity: usize,
+        mut on_block: Option<OnMetaBlock<'_>>,
+        keep_output: bool,
+    ) -> Result<(usize, usize), MetaBlockError> {
+        self.reset();
+
+        // A stream holds at least its header and last meta-block, so it is never empty.
+        if reader.remaining_slice().is_empty() {
+            return Err(MetaBlockError::EmptyInput);
+        }
+
+        self.window_bits = Some(read_window_bits(reader)?);
+
+        let start = self.open_window(capacity.min(max_len).min(limit));
+
+        let mut meta_blocks = 0;
+
+        loop {
+            let produced = self.produced;
+
+            // Decode one byte past the limit so that exceeding it can be detected.
+            self.context.max_len = (max_len - produced).min(limit.saturating_add(1) - produced);
+            let bit_offset = reader.bit_offset();
+            let mut block = MetaBlock::begin(reader, &self.context)?;
+
+            // A meta-block whose length takes the output past the limit is rejected before
+            // its commands are decoded, unless decoding would stop at `max_len` first. Without
+            // copies the output is shorter than the length, so it is checked as usual.
+            let length = block.header.length as u64;
+            if !block.header.is_metadata
+                && !self.context.literals_only
+                && max_len > limit
+                && produced as u64 + length > limit as u64
+            {
+                return Err(self.limit_error(limit));
+            }
+
+            if on_block.is_some() {
+                block.commands = Some(Vec::new());
+            }
+            while block.step(reader, &mut self.context)? != DecodeState::Done {}
+            meta_blocks += 1;
+            self.track_input(reader);
+
+            if let Some(on_block) = on_block.as_deref_mut() {
+                on_block(bit_offset, produced, &block);
+            }
+
+            self.produced += block.data.len();
+            if self.produced > limit {
+                return Err(self.limit_error(limit));
+            }
+
+            if !keep_output {
+                self.output_pos = self.context.window.len();
+                self.reclaim();
+            }
+
+            if self.produced >= max_len {
+                return Ok((start, meta_blocks));
+            }
+
+            if block.header.is_last {
+                break;
+            }
+        }
+
+        if self.strict {
+            check_padding(reader)?;
+        } else {
+            reader.align_to_byte();
+        }
+        self.finished = true;
+        self.track_input(reader);
+
+        Ok((start, meta_blocks))
+    }
+
+    /// Fill the window with the dictionary and any primed bytes, reserving room for
+    /// `capacity` output bytes after them, and return where the output starts.
+    ///
+    /// The primed bytes are consumed, so they only apply to the stream being opened.
+    fn open_window(&mut self, capacity: usize) -> usize {
+        let start = self.dictionary.len() + self.primed.len();
+        let window = &mut self.context.window;
+        window.reserve(start + capacity);
+        window.extend_from_slice(&self.dictionary);
+        window.extend_from_slice(&self.primed);
+        self.primed.clear();
+        self.output_start = start;
+        self.output_pos = start;
+        self.context.fast_literals = self.fast_literals;
+        self.context.literals_only = self.literals_only;
+        self.context.max_trees_per_block = self
+            .max_trees_per_block
+            .unwrap_or(DEFAULT_MAX_TREES_PER_META_BLOCK);
+        self.context.window_size = self.window_size().unwrap_or(usize::MAX);
+
+        start
+    }
+
+    /// Returns the maximum output length allowed for an input of `input_len` bytes.
+    fn output_limit(&self, input_len: usize) -> usize {
+        let mut limit = usize::MAX;
+        if let Some(max_output_len) = self.max_output_len {
+            limit = limit.min(max_output_len);
+        }
+        if let Some(ratio) = self.ratio_limit {
+            let ratio_len = (input_len as u64).saturating_mul(ratio as u64);
+            limit = limit.min(usize::try_from(ratio_len).unwrap_or(usize::MAX));
+        }
+
+        limit
+    }
+
+    /// Returns the error for exceeding the effective output limit.
+    fn limit_error(&self, limit: usize) -> MetaBlockError {
+        match self.max_output_len {
+            Some(max_output_len) if max_output_len == limit => MetaBlockError::OutputTooLarge,
+            _ => MetaBlockError::RatioLimitExceeded,
+        }
+    }
+}
+
+/// DecompressorBuilder configures the options of a Decompressor.
+#[derive(Debug, Clone, Default)]
+pub struct DecompressorBuilder {
+    /// The decompressor being configured.
+    decompressor: Decompressor,
+}
+
+impl DecompressorBuilder {
+    /// Creates a new DecompressorBuilder with default options.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of DecompressorBuilder.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{DecompressorBuilder, MetaBlockError};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// let mut decompressor = DecompressorBuilder::new()
+    ///     .strict(true)
+    ///     .max_output_len(4)
+    ///     .build();
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"abab");
+    ///
+    /// let mut decompressor = DecompressorBuilder::new().max_output_len(3).build();
+    /// assert!(matches!(
+    ///     decompressor.decompress(&stream),
+    ///     Err(MetaBlockError::OutputTooLarge)
+    /// ));
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            decompressor: Decompressor::new(),
+        }
+    }
+
+    /// Sets the maximum number of bytes the stream may decompress to.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The maximum output length in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::DecompressorBuilder;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// // Limits at the top of the address space do not wrap the output accounting.
+    /// let mut decompressor = DecompressorBuilder::new()
+    ///     .max_output_len(usize::MAX)
+    ///     .ratio_limit(u32::MAX)
+    ///     .build();
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"abab");
+    /// assert_eq!(decompressor.output_size(&stream, u64::MAX).unwrap(), 4);
+    /// ```
+    #[inline]
+    pub fn max_output_len(mut self, len: usize) -> Self {
+        self.decompressor.max_output_len = Some(len);
+        self
+    }
+
+    /// Enables or disables strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether to reject non-zero padding bits after the last meta-block.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.decompressor.strict = strict;
+        self
+    }
+
+    /// Sets a custom dictionary that backward references may reach into.
+    ///
+    /// The dictionary behaves as if it had been decoded immediately before the stream, so a
+    /// distance reaching past the start of the output reads from the end of the dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `dictionary` - The dictionary bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, DecompressorBuilder};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    /// writer.write_simple_code(&[b'!' as u16], 256).unwrap(); // literals
+    /// writer.write_simple_code(&[130], 704).unwrap(); // insert 0, copy 4
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance, initially 4
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = DecompressorBuilder::new()
+    ///     .dictionary(b"hello".to_vec())
+    ///     .build();
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"ello");
+    /// ```
+    #[inline]
+    pub fn dictionary(mut self, dictionary: Vec<u8>) -> Self {
+        self.decompressor.dictionary = dictionary;
+        self
+    }
+
+    /// Sets the maximum ratio of decompressed to compressed bytes.
+    ///
+    /// This guards against decompression bombs whose output is disproportionate to their
+    /// input, independently of any absolute output limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `ratio` - The maximum number of output bytes per input byte.
+    #[inline]
+    pub fn ratio_limit(mut self, ratio: u32) -> Self {
+        self.decompressor.ratio_limit = Some(ratio);
+        self
+    }
+
+    /// Enables or disables the fast path for literals.
+    ///
+    /// When the literal prefix code assigns every byte its own 8-bit value and the input is
+    /// byte-aligned, runs of literals are copied directly instead of decoded one symbol at a
+    /// time. The output is identical either way.
+    ///
+    /// # Arguments
+    ///
+    /// * `fast_literals` - Whether to enable the fast path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, DecompressorBuilder};
+    ///
+    /// // Insert codes whose extra bits (0 to 7) cover every alignment of the literals.
+    /// let inserts: [(u16, u32); 8] = [
+    ///     (5 << 3, 5),
+    ///     (6 << 3, 6),
+    ///     (256, 10),
+    ///     (256 + (2 << 3), 18),
+    ///     (256 + (4 << 3), 34),
+    ///     (256 + (6 << 3), 66),
+    ///     (448, 130),
+    ///     (448 + (1 << 3), 194),
+    /// ];
+    ///
+    /// let mut general = DecompressorBuilder::new().build();
+    /// let mut fast = DecompressorBuilder::new().fast_literals(true).build();
+    ///
+    /// for (extra_bits, (symbol, len)) in inserts.into_iter().enumerate() {
+    ///     let expected: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+    ///
+    ///     let mut writer = BitWriter::new();
+    ///     writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    ///     writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    ///     writer.write_bits(len - 1, 16).unwrap(); // MLEN
+    ///     writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    ///     writer.write_complex_code(&[8; 256]).unwrap(); // identity literal code
+    ///     writer.write_simple_code(&[symbol], 704).unwrap();
+    ///     writer.write_simple_code(&[0], 64).unwrap();
+    ///     if extra_bits > 0 {
+    ///         writer.write_bits(0, extra_bits as u8).unwrap();
+    ///     }
+    ///     for &byte in &expected {
+    ///         writer.write_bits(byte as u32, 8).unwrap();
+    ///     }
+    ///     let stream = writer.into_inner();
+    ///
+    ///     assert_eq!(general.decompress(&stream).unwrap(), expected);
+    ///     assert_eq!(fast.decompress(&stream).unwrap(), expected);
+    /// }
+    /// ```
+    #[inline]
+    pub fn fast_literals(mut self, fast_literals: bool) -> Self {
+        self.decompressor.fast_literals = fast_literals;
+        self
+    }
+
+    /// Sets the maximum number of prefix codes a meta-block may declare.
+    ///
+    /// This bounds the work and memory spent building prefix codes for a single meta-block,
+    /// complementing the output limits. It counts the codes for literals, insert-and-copy
+    /// lengths and distances, and is checked as their counts are read, before any code is
+    /// built. The default is `metablock::DEFAULT_MAX_TREES_PER_META_BLOCK`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_trees` - The maximum number of prefix codes per meta-block. Values above
+    ///   `metablock::MAX_TREES_PER_META_BLOCK`, the most the format allows, are clamped to
+    ///   it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{DecompressorBuilder, MetaBlockError};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// // One code for each of literals, insert-and-copy lengths and distances.
+    /// let mut decompressor = DecompressorBuilder::new().max_trees_per_block(3).build();
+    /// assert_eq!(decompressor.decompress(&stream).unwrap(), b"abab");
+    ///
+    /// let mut decompressor = DecompressorBuilder::new().max_trees_per_block(2).build();
+    /// assert!(matches!(
+    ///     decompressor.decompress(&stream),
+    ///     Err(MetaBlockError::TooManyTrees { count: 3, limit: 2 })
+    /// ));
+    /// ```
+    ///
+    /// A declared count over the limit is rejected before any prefix code is read:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitWriter, DecompressorBuilder, MetaBlockError};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 1).unwrap(); // NBLTYPESL = 1
+    /// writer.write_bits(0b1_111_1111111, 11).unwrap(); // NBLTYPESI = 256
+    /// let stream = writer.into_inner();
+    ///
+    /// let mut decompressor = DecompressorBuilder::new().max_trees_per_block(64).build();
+    /// assert!(matches!(
+    ///     decompressor.decompress(&stream),
+    ///     Err(MetaBlockError::TooManyTrees {
+    ///         count: 256,
+    ///         limit: 64
+    ///     })
+    /// ));
+    /// ```
+    #[inline]
+    pub fn max_trees_per_block(mut self, max_trees: usize) -> Self {
+        self.decompressor.max_trees_per_block = Some(max_trees.min(MAX_TREES_PER_META_BLOCK));
+        self
+    }
+
+    /// Enables or disables literals-only mode.
+    ///
+    /// In this mode copies are skipped, so the output holds only the literals inserted by
+    /// each command. It is lossy: repeated content is dropped and the output is generally
+    /// shorter than, and different from, the decompressed data. It suits quick content
+    /// previews and sniffing, not decompression.
+    ///
+    /// # Arguments
+    ///
+    /// * `literals_only` - Whether to skip copies.
+    #[inline]
+    pub fn literals_only(mut self, literals_only: bool) -> Self {
+        self.decompressor.literals_only = literals_only;
+        self
+    }
+
+    /// Builds the configured Decompressor.
+    ///
+    /// # Returns
+    ///
+    /// * The configured instance of Decompressor.
+    #[inline]
+    pub fn build(self) -> Decompressor {
+        self.decompressor
+    }
+}
+
+/// Decompress a complete Brotli stream with default options.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+///
+/// # Returns
+///
+/// * A Result containing the decompressed bytes, or an error if decoding fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{MetaBlockError, decompress};
+///
+/// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+/// let stream = *include_bytes!("../tests/fixtures/abab.br");
+/// assert_eq!(decompress(&stream).unwrap(), b"abab");
+///
+/// // An empty input is not a valid stream.
+/// assert!(matches!(decompress(&[]), Err(MetaBlockError::EmptyInput)));
+///
+/// // The WBITS pattern 1000001 is reserved.
+/// assert!(matches!(
+///     decompress(&[0b1000_0010]),
+///     Err(MetaBlockError::InvalidWindowBits)
+/// ));
+/// ```
+#[inline]
+pub fn decompress(input: &[u8]) -> Result<Decompressed, MetaBlockError> {
+    Decompressor::new().decompress(input)
+}
+
+/// Decompress a complete Brotli stream with default options, writing the output to `out`
+/// as it is decoded.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+/// * `out` - The writer to send the decompressed bytes to.
+///
+/// # Returns
+///
+/// * A Result containing the number of bytes written.
+///
+/// # Errors
+///
+/// * Returns the same errors as `Decompressor::decompress_to_writer`.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{decompress, decompress_to_writer};
+///
+/// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+/// let stream = *include_bytes!("../tests/fixtures/abab.br");
+///
+/// let mut out = Vec::new();
+/// assert_eq!(decompress_to_writer(&stream, &mut out).unwrap(), 4);
+/// assert_eq!(decompress(&stream).unwrap(), out);
+/// ```
+#[cfg(feature = "std")]
+pub fn decompress_to_writer<W: std::io::Write>(
+    input: &[u8],
+    out: &mut W,
+) -> Result<u64, MetaBlockError> {
+    Decompressor::new().decompress_to_writer(input, out)
+}
+
+/// Decompress a complete Brotli stream with default options and compute the CRC-32 of
+/// the output.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+///
+/// # Returns
+///
+/// * A Result containing the decompressed bytes and their CRC-32.
+///
+/// # Errors
+///
+/// * Returns the same errors as `decompress`.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{BitWriter, decompress_with_crc, metablock::write_uncompressed_block};
+///
+/// let data = b"The quick brown fox jumps over the lazy dog";
+///
+/// // Two meta-blocks, so that the CRC spans a meta-block boundary.
+/// let mut writer = BitWriter::new();
+/// writer.write_bit(false).unwrap(); // WBITS = 16
+/// write_uncompressed_block(&mut writer, &data[..10], false).unwrap();
+/// write_uncompressed_block(&mut writer, &data[10..], true).unwrap();
+///
+/// let (output, crc) = decompress_with_crc(&writer.into_inner()).unwrap();
+/// assert_eq!(output, data);
+/// assert_eq!(output.meta_blocks(), 3); // Including the empty last meta-block.
+/// assert_eq!(crc, 0x414f_a339);
+/// ```
+pub fn decompress_with_crc(input: &[u8]) -> Result<(Decompressed, u32), MetaBlockError> {
+    Decompressor::new().decompress_with_crc(input)
+}
+
+/// Extract the literal bytes of a Brotli stream, skipping copies.
+///
+/// This is lossy and does not decompress the stream: every command is parsed, but only
+/// its inserted literals are output and backward references are never resolved. Repeated
+/// content is therefore missing. It is faster than a full decode and useful as a rough
+/// preview, for example to guess whether a stream holds text.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+///
+/// # Returns
+///
+/// * A Result containing the literals of the stream, in order.
+///
+/// # Errors
+///
+/// * Returns the same errors as `decompress`, except that references beyond the window
+///   are not detected.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{decode_literals_only, decompress};
+///
+/// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+/// let stream = *include_bytes!("../tests/fixtures/abab.br");
+///
+/// assert_eq!(decompress(&stream).unwrap(), b"abab");
+/// assert_eq!(decode_literals_only(&stream).unwrap(), b"ab");
+/// ```
+pub fn decode_literals_only(input: &[u8]) -> Result<Vec<u8>, MetaBlockError> {
+    Decompressor::builder()
+        .literals_only(true)
+        .build()
+        .decompress(input)
+        .map(Vec::from)
+}
+
+/// Decompress a complete Brotli stream with default options, reserving room for
+/// `expected_output` bytes up front.
+///
+/// With default options there is no output limit, so use a configured `Decompressor` with
+/// `max_output_len` when the hint comes from untrusted input.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+/// * `expected_output` - The expected decompressed length.
+///
+/// # Returns
+///
+/// * A Result containing the decompressed bytes, or an error if decoding fails.
+#[inline]
+pub fn decompress_with_capacity(
+    input: &[u8],
+    expected_output: usize,
+) -> Result<Decompressed, MetaBlockError> {
+    Decompressor::new().decompress_with_capacity(input, expected_output)
+}
+
+/// Decompress a complete Brotli stream with default options into a caller-provided buffer.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+/// * `out` - The buffer to write the decompressed bytes into.
+///
+/// # Returns
+///
+/// * A Result containing the number of bytes written to the front of `out`.
+///
+/// # Errors
+///
+/// * Returns the same errors as `Decompressor::decompress_into`.
+#[inline]
+pub fn decompress_into(input: &[u8], out: &mut [u8]) -> Result<usize, MetaBlockError> {
+    Decompressor::new().decompress_into(input, out)
+}
+
+/// Decompress a complete Brotli stream against a custom dictionary.
+///
+/// The dictionary behaves as if it had been decoded immediately before the stream, as set by
+/// `DecompressorBuilder::dictionary`. References to the static dictionary are not supported
+/// yet and return `MetaBlockError::Unsupported`.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+/// * `dictionary` - The dictionary the stream was compressed with.
+///
+/// # Returns
+///
+/// * A Result containing the decompressed bytes, or an error if decoding fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{BitWriter, MetaBlockError, decompress_with_dictionary};
+///
+/// let mut writer = BitWriter::new();
+/// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+/// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+/// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+/// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+/// writer.write_simple_code(&[b'!' as u16], 256).unwrap(); // literals
+/// writer.write_simple_code(&[130], 704).unwrap(); // insert 0, copy 4
+/// writer.write_simple_code(&[0], 64).unwrap(); // last distance, initially 4
+/// let stream = writer.into_inner();
+///
+/// let output = decompress_with_dictionary(&stream, b"hello").unwrap();
+/// assert_eq!(output, b"ello");
+///
+/// // Without a dictionary the reference lands in the static dictionary.
+/// assert!(matches!(
+///     decompress_with_dictionary(&stream, b""),
+///     Err(MetaBlockError::Unsupported)
+/// ));
+/// ```
+pub fn decompress_with_dictionary(
+    input: &[u8],
+    dictionary: &[u8],
+) -> Result<Decompressed, MetaBlockError> {
+    Decompressor::builder()
+        .dictionary(dictionary.to_vec())
+        .build()
+        .decompress(input)
+}
+
+/// Decompress every Brotli stream concatenated in `input` with default options.
+///
+/// # Arguments
+///
+/// * `input` - One or more compressed streams, back to back.
+///
+/// # Returns
+///
+/// * A Result containing the decompressed bytes of each stream, in order.
+#[inline]
+pub fn decompress_all(input: &[u8]) -> Result<Vec<Decompressed>, MetaBlockError> {
+    Decompressor::new().decompress_all(input)
+}
+
+/// Decode a stream with default options and report the boundaries of its meta-blocks.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+///
+/// # Returns
+///
+/// * A Result containing a summary of each meta-block, in stream order.
+#[inline]
+pub fn summarize_meta_blocks(input: &[u8]) -> Result<Vec<MetaBlockSummary>, MetaBlockError> {
+    Decompressor::new().summarize_meta_blocks(input)
+}
+
+/// Decode a stream with default options and outline its structure.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+///
+/// # Returns
+///
+/// * A Result containing the outline of the stream, which can be printed with `Display`.
+#[inline]
+pub fn outline(input: &[u8]) -> Result<StreamOutline, MetaBlockError> {
+    Decompressor::new().outline(input)
+}
+
+/// Compute the total decompressed size of a stream with default options, giving up once it
+/// exceeds `max_size`.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+/// * `max_size` - The largest total size to accept.
+///
+/// # Returns
+///
+/// * A Result containing the sum of MLEN over all meta-blocks.
+#[inline]
+pub fn output_size(input: &[u8], max_size: u64) -> Result<u64, MetaBlockError> {
+    Decompressor::new().output_size(input, max_size)
+}
+
+/// Decompress at most the first `max_bytes` bytes of a Brotli stream with default options.
+///
+/// # Arguments
+///
+/// * `input` - The compressed stream.
+/// * `max_bytes` - The maximum number of bytes to decompress.
+///
+/// # Returns
+///
+/// * A Result containing up to `max_bytes` decompressed bytes, or an error if decoding
+///   fails before then.
+#[inline]
+pub fn decompress_prefix(input: &[u8], max_bytes: usize) -> Result<Decompressed, MetaBlockError> {
+    Decompressor::new().decompress_prefix(input, max_bytes)
+}
+
+/// Returns whether the input plausibly starts with a Brotli stream.
+///
+/// Only the stream header and the first meta-block header are parsed, which takes at most a
+/// few bytes and allocates nothing. Brotli has no magic number, so this is a heuristic for
+/// format detection: a false result is definite, but a true result does not guarantee that
+/// the stream decodes.
+///
+/// # Arguments
+///
+/// * `input` - The data to check.
+///
+/// # Returns
+///
+/// * `true` if the headers parse, `false` otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::is_probably_brotli;
+///
+/// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+/// let stream = *include_bytes!("../tests/fixtures/abab.br");
+/// assert!(is_probably_brotli(&stream));
+///
+/// assert!(!is_probably_brotli(&[]));
+/// // A reserved window size.
+/// assert!(!is_probably_brotli(&[0b1000_0010]));
+/// // A metadata meta-block with the reserved bit set.
+/// assert!(!is_probably_brotli(&[0b0011_1000]));
+/// ```
+pub fn is_probably_brotli(input: &[u8]) -> bool {
+    peek_first_header(input).is_ok()
+}
+
+/// Parse the stream header and the header of the first meta-block, without decoding it.
+///
+/// This lets a caller inspect the first meta-block, for example to take a fast copy path
+/// when it is uncompressed, before committing to a full decode.
+///
+/// # Arguments
+///
+/// * `input` - The compressed data.
+///
+/// # Returns
+///
+/// * A Result containing the header of the first meta-block.
+///
+/// # Errors
+///
+/// * Returns `MetaBlockError::EmptyInput` if the input is empty.
+/// * Returns `MetaBlockError::InvalidWindowBits` if the stream header is reserved.
+/// * Returns `MetaBlockError::Corrupt` if the meta-block header is malformed.
+/// * Returns `MetaBlockError::NeedMoreInput` if the meta-block header is truncated.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{BitWriter, MetaBlockError, MetaBlockHeader, peek_first_header};
+///
+/// let header = MetaBlockHeader {
+///     is_last: false,
+///     is_last_empty: false,
+///     length: 3,
+///     is_uncompressed: true,
+///     is_metadata: false,
+/// };
+///
+/// let mut writer = BitWriter::new();
+/// writer.write_bits(0, 1).unwrap(); // WBITS = 16
+/// header.write(&mut writer).unwrap();
+/// // Only the header is inspected, so the body may be missing.
+/// let data = writer.into_inner();
+///
+/// let first = peek_first_header(&data).unwrap();
+/// assert!(first.is_uncompressed);
+/// assert_eq!(first.length, 3);
+///
+/// assert!(matches!(peek_first_header(&[]), Err(MetaBlockError::EmptyInput)));
+/// assert!(matches!(
+///     peek_first_header(&[0b1000_0010]),
+///     Err(MetaBlockError::InvalidWindowBits)
+/// ));
+/// ```
+pub fn peek_first_header(input: &[u8]) -> Result<MetaBlockHeader, MetaBlockError> {
+    if input.is_empty() {
+        return Err(MetaBlockError::EmptyInput);
+    }
+
+    let mut reader = BitReader::new(input);
+    read_window_bits(&mut reader)?;
+
+    MetaBlockHeader::read(&mut reader)
+}
+
+/// The prefix code of the WBITS stream header, as `(bits, len, WBITS)`.
+///
+/// The 7-bit pattern `1000001` is reserved and deliberately absent.
+const WINDOW_BITS_CODES: [(u8, u8, u32); 15] = [
+    (0b0, 1, 16),
+    (0b1001, 4, 18),
+    (0b1010, 4, 19),
+    (0b1011, 4, 20),
+    (0b1100, 4, 21),
+    (0b1101, 4, 22),
+    (0b1110, 4, 23),
+    (0b1111, 4, 24),
+    (0b100_0000, 7, 17),
+    (0b100_0010, 7, 10),
+    (0b100_0011, 7, 11),
+    (0b100_0100, 7, 12),
+    (0b100_0101, 7, 13),
+    (0b100_0110, 7, 14),
+    (0b100_0111, 7, 15),
+];
+
+/// Read the WBITS stream header and return the window size exponent.
+fn read_window_bits(reader: &mut BitReader) -> Result<u8, MetaBlockError> {
+    match reader.read_prefix(&WINDOW_BITS_CODES) {
+        Ok(window_bits) => Ok(window_bits as u8),
+        Err(BitReaderError::NoPrefixMatch) => Err(MetaBlockError::InvalidWindowBits),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Check that the remaining bits of the current byte are all zero.
+fn check_padding(reader: &mut BitReader) -> Result<(), MetaBlockError> {
+    match reader.align_to_byte_checked() {
+        Ok(_) => Ok(()),
+        Err(BitReaderError::NonZeroPadding) => Err(MetaBlockError::NonZeroPadding),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,128 @@
+/// The operation a transform applies to a dictionary word, between its prefix and suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformKind {
+    /// The word is used as is.
+    Identity,
+    /// The last `n` bytes of the word are dropped (1-9).
+    OmitLast(u8),
+    /// The first character of the word is converted to upper case.
+    UppercaseFirst,
+    /// Every character of the word is converted to upper case.
+    UppercaseAll,
+    /// The first `n` bytes of the word are dropped (1-9).
+    OmitFirst(u8),
+}
+
+/// A transform turning a dictionary word into the bytes a static dictionary reference
+/// copies, as described in RFC 7932 section 8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transform {
+    /// The bytes inserted before the word.
+    pub prefix: Vec<u8>,
+    /// The operation applied to the word itself.
+    pub kind: TransformKind,
+    /// The bytes appended after the word.
+    pub suffix: Vec<u8>,
+}
+
+impl Transform {
+    /// Apply the transform to `word`, appending the result to `out`.
+    ///
+    /// Upper-casing follows RFC 7932: it flips the case of ASCII letters and alters the
+    /// second or third byte of multi-byte UTF-8 sequences, without validating them.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - The dictionary word.
+    /// * `out` - The buffer to append the transformed word to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::dictionary::{Transform, TransformKind};
+    ///
+    /// let transform = Transform {
+    ///     prefix: b" ".to_vec(),
+    ///     kind: TransformKind::UppercaseFirst,
+    ///     suffix: b", ".to_vec(),
+    /// };
+    /// let mut out = Vec::new();
+    /// transform.apply(b"time", &mut out);
+    /// assert_eq!(out, b" Time, ");
+    ///
+    /// let transform = Transform {
+    ///     prefix: Vec::new(),
+    ///     kind: TransformKind::OmitFirst(2),
+    ///     suffix: Vec::new(),
+    /// };
+    /// let mut out = Vec::new();
+    /// transform.apply(b"brotli", &mut out);
+    /// assert_eq!(out, b"otli");
+    ///
+    /// // A word with nothing left to upper-case is passed through.
+    /// let transform = Transform {
+    ///     prefix: b"<".to_vec(),
+    ///     kind: TransformKind::UppercaseFirst,
+    ///     suffix: b">".to_vec(),
+    /// };
+    /// let mut out = Vec::new();
+    /// transform.apply(b"", &mut out);
+    /// assert_eq!(out, b"<>");
+    /// ```
+    pub fn apply(&self, word: &[u8], out: &mut Vec<u8>) {
+        let word = match self.kind {
+            TransformKind::OmitFirst(n) => &word[(n as usize).min(word.len())..],
+            TransformKind::OmitLast(n) => &word[..word.len().saturating_sub(n as usize)],
+            _ => word,
+        };
+
+        out.extend_from_slice(&self.prefix);
+        let start = out.len();
+        out.extend_from_slice(word);
+
+        let transformed = &mut out[start..];
+        match self.kind {
+            TransformKind::UppercaseFirst => {
+                to_upper_case(transformed);
+            }
+            TransformKind::UppercaseAll => {
+                let mut i = 0;
+                while i < transformed.len() {
+                    i += to_upper_case(&mut transformed[i..]);
+                }
+            }
+            _ => {}
+        }
+
+        out.extend_from_slice(&self.suffix);
+    }
+}
+
+/// Upper-case the character at the start of `bytes` and return its length in bytes, or 0
+/// if `bytes` is empty.
+fn to_upper_case(bytes: &mut [u8]) -> usize {
+    let Some(&first) = bytes.first() else {
+        return 0;
+    };
+
+    match first {
+        0..192 => {
+            if first.is_ascii_lowercase() {
+                bytes[0] ^= 32;
+            }
+            1
+        }
+        192..224 => {
+            if let Some(byte) = bytes.get_mut(1) {
+                *byte ^= 32;
+            }
+            2
+        }
+        _ => {
+            if let Some(byte) = bytes.get_mut(2) {
+                *byte ^= 5;
+            }
+            3
+        }
+    }
+}
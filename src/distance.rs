@@ -0,0 +1,217 @@
+use crate::BitReader;
+use crate::error::BitReaderError;
+
+/// Number of distance codes that refer to the distance ring buffer.
+pub const NUM_DISTANCE_SHORT_CODES: u32 = 16;
+
+/// Initial contents of the distance ring buffer, from oldest to most recent.
+pub const INITIAL_DISTANCES: [u32; 4] = [16, 15, 11, 4];
+
+/// Ring buffer entry each short distance code is derived from (0 is the most recent).
+pub(crate) const SHORT_CODE_INDEX: [u8; 16] = [0, 1, 2, 3, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1];
+
+/// Offset each short distance code adds to its ring buffer entry.
+const SHORT_CODE_OFFSET: [i64; 16] = [0, 0, 0, 0, -1, 1, -2, 2, -3, 3, -1, 1, -2, 2, -3, 3];
+
+/// DistanceRing holds the last four backward distances used by a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceRing {
+    /// The last four distances, from oldest to most recent.
+    distances: [u32; 4],
+}
+
+impl DistanceRing {
+    /// Creates a new DistanceRing holding the initial distances defined by the format.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of DistanceRing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::DistanceRing;
+    /// use brotschneider::distance::INITIAL_DISTANCES;
+    ///
+    /// let ring = DistanceRing::new();
+    /// assert_eq!(ring.last(0), INITIAL_DISTANCES[3]);
+    /// assert_eq!(ring.last(3), INITIAL_DISTANCES[0]);
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            distances: INITIAL_DISTANCES,
+        }
+    }
+
+    /// Returns the `n`-th most recent distance, where 0 is the last distance used.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if `n` is greater than 3.
+    #[inline]
+    pub fn last(&self, n: usize) -> u32 {
+        self.distances[3 - n]
+    }
+
+    /// Pushes a distance, evicting the oldest one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::DistanceRing;
+    ///
+    /// let mut ring = DistanceRing::new();
+    /// ring.push(100);
+    /// assert_eq!(ring.last(0), 100);
+    /// assert_eq!(ring.last(1), 4);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, distance: u32) {
+        self.distances.rotate_left(1);
+        self.distances[3] = distance;
+    }
+
+    /// Resolve a short distance code (0-15) against the ring.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The short distance code.
+    ///
+    /// # Returns
+    ///
+    /// * The resolved distance, or `None` if the code is not a short code or the resolved
+    ///   distance is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::DistanceRing;
+    ///
+    /// let ring = DistanceRing::new();
+    /// assert_eq!(ring.resolve_short_code(1), Some(11));
+    /// assert_eq!(ring.resolve_short_code(5), Some(5)); // last + 1
+    /// assert_eq!(ring.resolve_short_code(14), Some(8)); // second last - 3
+    /// ```
+    pub fn resolve_short_code(&self, code: u32) -> Option<u32> {
+        let code = code as usize;
+        if code >= SHORT_CODE_INDEX.len() {
+            return None;
+        }
+
+        let distance = self.last(SHORT_CODE_INDEX[code] as usize) as i64 + SHORT_CODE_OFFSET[code];
+        if distance <= 0 {
+            return None;
+        }
+
+        Some(distance as u32)
+    }
+}
+
+impl Default for DistanceRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The distance code parameters (NPOSTFIX and NDIRECT) of a meta-block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DistanceParams {
+    /// The number of postfix bits (0-3).
+    pub npostfix: u8,
+    /// The number of direct distance codes.
+    pub ndirect: u32,
+}
+
+impl DistanceParams {
+    /// Read NPOSTFIX and NDIRECT from the meta-block header.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the distance parameters.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    pub fn read(reader: &mut BitReader) -> Result<Self, BitReaderError> {
+        let npostfix = reader.read_bits(2)? as u8;
+        let ndirect = reader.read_bits(4)? << npostfix;
+
+        Ok(Self { npostfix, ndirect })
+    }
+
+    /// Returns the size of the distance alphabet for these parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `16 + NDIRECT + (48 << NPOSTFIX)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::distance::DistanceParams;
+    ///
+    /// let params = DistanceParams {
+    ///     npostfix: 0,
+    ///     ndirect: 1,
+    /// };
+    /// assert_eq!(params.alphabet_size(), 65);
+    /// ```
+    #[inline]
+    pub fn alphabet_size(&self) -> usize {
+        NUM_DISTANCE_SHORT_CODES as usize + self.ndirect as usize + (48 << self.npostfix)
+    }
+
+    /// Decode a distance code that is not a short code, reading its extra bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The distance code (at least 16).
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decoded distance.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitReaderError::UnexpectedEndOfInput` if the input ends early.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::BitReader;
+    /// use brotschneider::distance::DistanceParams;
+    ///
+    /// let params = DistanceParams {
+    ///     npostfix: 0,
+    ///     ndirect: 4,
+    /// };
+    ///
+    /// // Direct codes map to distances 1..=NDIRECT without extra bits.
+    /// let mut reader = BitReader::new(&[]);
+    /// assert_eq!(params.decode(18, &mut reader).unwrap(), 3);
+    ///
+    /// // The first code after the direct codes has one extra bit.
+    /// let mut reader = BitReader::new(&[0b1000_0000]);
+    /// assert_eq!(params.decode(20, &mut reader).unwrap(), 6);
+    /// ```
+    pub fn decode(&self, code: u32, reader: &mut BitReader) -> Result<u32, BitReaderError> {
+        if code < NUM_DISTANCE_SHORT_CODES + self.ndirect {
+            return Ok(code - NUM_DISTANCE_SHORT_CODES + 1);
+        }
+
+        let code = code - NUM_DISTANCE_SHORT_CODES - self.ndirect;
+        let postfix_mask = (1 << self.npostfix) - 1;
+        let hcode = code >> self.npostfix;
+        let lcode = code & postfix_mask;
+        let nbits = 1 + (code >> (self.npostfix + 1));
+        let offset = reader.read_value(((2 + (hcode & 1)) << nbits) - 4, nbits as u8)?;
+
+        Ok((offset << self.npostfix) + lcode + self.ndirect + 1)
+    }
+}
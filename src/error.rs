@@ -11,6 +11,30 @@ pub enum BitReaderError {
     /// Attempted to read past the end of the data.
     #[error("Unexpected end of input")]
     UnexpectedEndOfInput,
+
+    /// The bits skipped to reach a byte boundary were not all zero.
+    #[error("Non-zero padding bits before byte boundary")]
+    NonZeroPadding,
+
+    /// A byte-aligned read was attempted in the middle of a byte.
+    #[error("Reader is not byte-aligned")]
+    NotByteAligned,
+
+    /// The upcoming bits match none of the patterns of a prefix code table.
+    #[error("No prefix code matches the upcoming bits")]
+    NoPrefixMatch,
+
+    /// Attempted to move the cursor before the start of the data.
+    #[error("Cannot rewind {0} bits before the start of input")]
+    RewindBeforeStart(usize),
+
+    /// A reader was positioned outside of its data.
+    #[error("Invalid position: byte {byte_pos}, bit {bit_pos}")]
+    InvalidPosition { byte_pos: usize, bit_pos: u8 },
+
+    /// A field was read with a value above its largest allowed value.
+    #[error("Value {value} exceeds the maximum of {max}")]
+    ValueOutOfRange { value: u32, max: u32 },
 }
 
 /// Errors that can occur while writing bits.
@@ -19,6 +43,56 @@ pub enum BitReaderError {
 pub enum BitWriterError {
     #[error("attempted to write too many bits ({0})")]
     TooManyBits(u8),
+
+    /// The code lengths do not describe a complete prefix code.
+    #[error("invalid prefix code lengths")]
+    InvalidCodeLengths,
+
+    /// The symbols do not describe a valid simple prefix code.
+    #[error("invalid simple prefix code symbols")]
+    InvalidSimpleCode,
+
+    /// The meta-block header fields cannot be encoded.
+    #[error("invalid meta-block header")]
+    InvalidMetaBlockHeader,
+}
+
+/// Errors that can occur while encoding a stream.
+///
+/// Encoder code that writes bits and builds prefix codes can use `?` on both kinds of
+/// failure.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{BitWriter, EncodeError, HuffmanTree};
+///
+/// fn write_code(frequencies: &[u32], writer: &mut BitWriter) -> Result<(), EncodeError> {
+///     let tree = HuffmanTree::from_frequencies(frequencies)?;
+///     let (code, len) = tree.code_for(0).unwrap_or((0, 0));
+///     writer.write_bits(code, len)?;
+///     writer.write_bits(0, 33)?;
+///     Ok(())
+/// }
+///
+/// let mut writer = BitWriter::new();
+/// assert!(matches!(
+///     write_code(&[0, 0], &mut writer),
+///     Err(EncodeError::Huffman(_))
+/// ));
+/// assert!(matches!(
+///     write_code(&[1, 1], &mut writer),
+///     Err(EncodeError::BitWriter(_))
+/// ));
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum EncodeError {
+    #[error("Bit writing error: {0}")]
+    BitWriter(#[from] BitWriterError),
+
+    #[error("Huffman encoding error: {0}")]
+    Huffman(#[from] HuffmanError),
 }
 
 /// Errors that can occur while decoding Huffman codes.
@@ -31,6 +105,26 @@ pub enum HuffmanError {
     #[error("Incomplete Huffman tree")]
     IncompleteTree,
 
+    /// A code length is longer than the 15 bits Brotli allows.
+    #[error("Code length {0} exceeds the maximum of 15")]
+    CodeTooLong(u8),
+
+    /// Every code length is zero, so the code has no symbols.
+    #[error("Empty alphabet: no symbol has a code")]
+    EmptyAlphabet,
+
+    /// The encoded prefix code is malformed.
+    #[error("Corrupt prefix code: {reason}")]
+    Corrupt { reason: &'static str },
+
+    /// A decoded symbol is outside the alphabet the tree was built for.
+    #[error("Decoded symbol {0} is outside the alphabet")]
+    SymbolOutOfRange(u16),
+
+    /// Decoding consumed more bits than the caller's budget allows.
+    #[error("Bit budget exceeded")]
+    BudgetExceeded,
+
     #[error("Read error: {0}")]
     BitReaderError(#[from] BitReaderError),
 }
@@ -43,6 +137,46 @@ pub enum MetaBlockError {
     #[error("Huffman decoding error: {0}")]
     Huffman(#[from] HuffmanError),
 
+    /// A meta-block declares more prefix codes than the configured limit.
+    #[error("Meta-block declares {count} prefix codes, more than the limit of {limit}")]
+    TooManyTrees { count: usize, limit: usize },
+
+    /// The input ended in the middle of the stream.
+    #[error("Unexpected end of input at bit {bit_offset}")]
+    NeedMoreInput {
+        /// The bit offset the reader had reached when it ran out of input.
+        bit_offset: usize,
+    },
+
     #[error("Unsupported feature in meta-block")]
     Unsupported,
+
+    /// The meta-block is malformed.
+    #[error("Corrupt meta-block: {reason}")]
+    Corrupt { reason: &'static str },
+
+    /// The input is empty, which is not a valid Brotli stream.
+    #[error("Empty input")]
+    EmptyInput,
+
+    /// The stream header encodes a reserved window size.
+    #[error("Invalid window size in stream header")]
+    InvalidWindowBits,
+
+    /// The unused bits after the last meta-block are not all zero (strict mode only).
+    #[error("Non-zero padding bits after last meta-block")]
+    NonZeroPadding,
+
+    /// The decompressed output exceeds the configured maximum length.
+    #[error("Decompressed output exceeds the maximum length")]
+    OutputTooLarge,
+
+    /// The decompressed output exceeds the configured ratio to the compressed input.
+    #[error("Decompressed output exceeds the compression ratio limit")]
+    RatioLimitExceeded,
+
+    /// The underlying reader failed while supplying compressed input.
+    #[cfg(feature = "std")]
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
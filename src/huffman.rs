@@ -1,17 +1,87 @@
 use crate::BitReader;
-use crate::error::HuffmanError;
+use crate::error::{BitReaderError, HuffmanError};
 
+/// HuffmanTree decodes symbols of a canonical prefix code.
+///
+/// # Thread safety
+///
+/// A tree is immutable once built, so it is `Send + Sync` and can be shared across threads
+/// decoding independent streams. This is checked at compile time; any future lookup variant
+/// must keep the guarantee.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use brotschneider::{BitReader, HuffmanTree};
+///
+/// let tree = Arc::new(HuffmanTree::from_code_lengths(&[1, 2, 2]).unwrap());
+///
+/// let workers: Vec<_> = [0b0000_0000u8, 0b1000_0000, 0b1100_0000]
+///     .into_iter()
+///     .map(|byte| {
+///         let tree = Arc::clone(&tree);
+///         thread::spawn(move || tree.decode_symbol(&mut BitReader::new(&[byte])).unwrap())
+///     })
+///     .collect();
+///
+/// let symbols: Vec<u16> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+/// assert_eq!(symbols, [0, 1, 2]);
+/// ```
+#[derive(Debug, Clone)]
 pub struct HuffmanTree {
     /// Map from Huffman code to symbol.
     pub lookup: Vec<u16>,
     /// Number of bits in the longest code.
     pub max_bits: u8,
+    /// Number of symbols in the alphabet; decoded symbols must be below it.
+    pub alphabet_size: usize,
+    /// The code length of each symbol the tree was built from, 0 for unused symbols.
+    pub code_lengths: Vec<u8>,
 }
 
+/// HuffmanScratch holds working memory for building Huffman trees.
+///
+/// Reusing one across calls to `HuffmanTree::from_code_lengths_with_scratch` avoids
+/// allocating the per-length counters for every tree.
+#[derive(Debug, Clone, Default)]
+pub struct HuffmanScratch {
+    /// Number of codes of each length.
+    bl_count: Vec<u16>,
+    /// Next canonical code to assign for each length.
+    next_code: Vec<u32>,
+}
+
+impl HuffmanScratch {
+    /// Creates a new, empty HuffmanScratch.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of HuffmanScratch.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            bl_count: Vec::new(),
+            next_code: Vec::new(),
+        }
+    }
+}
+
+// Fails to compile if HuffmanTree ever stops being shareable across threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<HuffmanTree>();
+};
+
 impl HuffmanTree {
     /// Construct a canonical Huffman tree from code lengths.
     /// Each entry in `code_lengths` is the code length (in bits) for the symbol with that index.
     ///
+    /// If only one symbol has a non-zero code length, the tree decodes that symbol without
+    /// consuming any bits, as Brotli's single-symbol prefix codes require.
+    ///
     /// # Arguments
     ///
     /// * `code_lengths` - A slice of u8 representing the code lengths for each symbol.
@@ -23,80 +93,175 @@ impl HuffmanTree {
     /// # Errors
     ///
     /// * Returns `HuffmanError::OverfullTree` if the tree is overfull.
+    /// * Returns `HuffmanError::EmptyAlphabet` if every code length is zero.
     /// * Returns `HuffmanError::IncompleteTree` if the tree is incomplete.
+    /// * Returns `HuffmanError::CodeTooLong` if a code length exceeds `MAX_CODE_LENGTH`.
+    /// * Returns `HuffmanError::Corrupt` if a code length exceeds the computed maximum, which
+    ///   indicates an internal inconsistency.
     /// * Returns `HuffmanError::BitReaderError` if there is an error reading bits.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use brotschneider::HuffmanTree;
+    /// use brotschneider::{HuffmanError, HuffmanTree};
     ///
     /// let code_lengths = [2, 2, 2, 2];
     /// let tree = HuffmanTree::from_code_lengths(&code_lengths).unwrap();
     ///
     /// assert_eq!(tree.max_bits, 2);
     /// assert_eq!(tree.lookup.len(), 4);
+    ///
+    /// let single = HuffmanTree::from_code_lengths(&[0, 0, 1]).unwrap();
+    /// assert_eq!(single.max_bits, 0);
+    /// assert_eq!(single.lookup, vec![2]);
+    ///
+    /// // No symbol has a code.
+    /// assert!(matches!(
+    ///     HuffmanTree::from_code_lengths(&[0; 4]),
+    ///     Err(HuffmanError::EmptyAlphabet)
+    /// ));
+    ///
+    /// // Only 8 symbols fit in a tree of depth 3.
+    /// assert!(matches!(
+    ///     HuffmanTree::from_code_lengths(&[3; 9]),
+    ///     Err(HuffmanError::OverfullTree(3))
+    /// ));
+    ///
+    /// // Lengths are rejected before a lookup table of 2^len entries is allocated.
+    /// let mut lengths: Vec<u8> = (1..=15).collect();
+    /// lengths.push(16);
+    /// lengths.push(16);
+    /// assert!(matches!(
+    ///     HuffmanTree::from_code_lengths(&lengths),
+    ///     Err(HuffmanError::CodeTooLong(16))
+    /// ));
+    /// assert!(matches!(
+    ///     HuffmanTree::from_code_lengths(&[0, 255]),
+    ///     Err(HuffmanError::CodeTooLong(255))
+    /// ));
     /// ```
     pub fn from_code_lengths(code_lengths: &[u8]) -> Result<Self, HuffmanError> {
-        let mut max_bits = 0u8;
-        for &len in code_lengths {
-            if len > max_bits {
-                max_bits = len;
-            }
-        }
-
-        if max_bits == 0 {
-            return Err(HuffmanError::IncompleteTree);
-        }
-
-        let mut bl_count = vec![0u16; (max_bits + 1) as usize];
-        for &len in code_lengths {
-            if len != 0 {
-                bl_count[len as usize] += 1;
-            }
-        }
-
-        let mut code = 0u32;
-        let mut next_code = vec![0u32; (max_bits + 1) as usize];
-
-        for bits in 1..=max_bits {
-            code = (code + bl_count[(bits - 1) as usize] as u32) << 1;
-            next_code[bits as usize] = code;
-        }
-
-        let mut total_codes = 0u32;
-
-        for bits in 1..=max_bits {
-            total_codes = (total_codes << 1) + bl_count[bits as usize] as u32;
-        }
-
-        if total_codes != (1u32 << max_bits) {
-            if total_codes > (1u32 << max_bits) {
-                return Err(HuffmanError::OverfullTree(max_bits));
-            }
-
-            return Err(HuffmanError::IncompleteTree);
-        }
+        HuffmanTree::from_code_lengths_with_scratch(code_lengths, &mut HuffmanScratch::new())
+    }
 
-        let table_size = 1 << max_bits;
-        let mut lookup = vec![0xffffu16; table_size];
+    /// Construct a canonical Huffman tree for symbols with the given frequencies.
+    ///
+    /// Code lengths are assigned by `lengths_from_frequencies` with the 15-bit limit of the
+    /// format, so the tree can be written to a stream with `BitWriter::write_complex_code`.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequencies` - The number of occurrences of each symbol.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `HuffmanError::EmptyAlphabet` if every frequency is zero.
+    /// * Returns `HuffmanError::OverfullTree` if more than `2^15` symbols are used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::HuffmanTree;
+    ///
+    /// let tree = HuffmanTree::from_frequencies(&[10, 6, 2, 1, 1]).unwrap();
+    /// assert_eq!(tree.code_for(0), Some((0b0, 1)));
+    /// assert_eq!(tree.code_for(1), Some((0b10, 2)));
+    /// assert_eq!(tree.code_for(4), Some((0b1111, 4)));
+    /// assert!(tree.is_complete());
+    /// tree.self_check().unwrap();
+    /// ```
+    pub fn from_frequencies(frequencies: &[u32]) -> Result<Self, HuffmanError> {
+        let lengths = lengths_from_frequencies(frequencies, MAX_CODE_LENGTH)?;
+        HuffmanTree::from_code_lengths(&lengths)
+    }
 
-        for (symbol, &len) in code_lengths.iter().enumerate() {
-            if len != 0 {
-                let code_val = next_code[len as usize];
-                next_code[len as usize] += 1;
+    /// Construct a canonical Huffman tree from code lengths, reusing `scratch` for working
+    /// memory.
+    ///
+    /// This behaves exactly like `from_code_lengths`, but avoids allocating working buffers
+    /// on every call when many trees are built in a row.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_lengths` - A slice of u8 representing the code lengths for each symbol.
+    /// * `scratch` - Working memory, reused across calls.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `HuffmanTree::from_code_lengths`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::huffman::HuffmanScratch;
+    /// use brotschneider::HuffmanTree;
+    ///
+    /// let mut scratch = HuffmanScratch::new();
+    /// for lengths in [&[2, 2, 2, 2][..], &[1, 2, 3, 3], &[1, 1]] {
+    ///     let tree = HuffmanTree::from_code_lengths_with_scratch(lengths, &mut scratch).unwrap();
+    ///     assert_eq!(tree.lookup, HuffmanTree::from_code_lengths(lengths).unwrap().lookup);
+    /// }
+    /// ```
+    pub fn from_code_lengths_with_scratch(
+        code_lengths: &[u8],
+        scratch: &mut HuffmanScratch,
+    ) -> Result<Self, HuffmanError> {
+        let mut lookup = Vec::new();
+        let max_bits = build_lookup(code_lengths, scratch, &mut lookup)?;
 
-                let prefix = code_val << (max_bits - len);
-                let fill_count = 1 << (max_bits - len);
+        Ok(HuffmanTree {
+            lookup,
+            max_bits,
+            alphabet_size: code_lengths.len(),
+            code_lengths: code_lengths.to_vec(),
+        })
+    }
 
-                for i in 0..fill_count {
-                    let idx = (prefix | i) as usize;
-                    lookup[idx] = symbol as u16;
-                }
-            }
-        }
+    /// Rebuild `tree` from code lengths, reusing its buffers and `scratch`.
+    ///
+    /// The lookup table is resized and refilled in place, so once a tree has held the
+    /// largest table needed, building further trees into it does not allocate. The result
+    /// is identical to `from_code_lengths`.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_lengths` - A slice of u8 representing the code lengths for each symbol.
+    /// * `scratch` - Working memory, reused across calls.
+    /// * `tree` - The tree to rebuild.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `HuffmanTree::from_code_lengths`. On error, the
+    ///   contents of `tree` are unspecified and it must be rebuilt before use.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::huffman::HuffmanScratch;
+    /// use brotschneider::HuffmanTree;
+    ///
+    /// let mut scratch = HuffmanScratch::new();
+    /// let mut tree = HuffmanTree::from_code_lengths(&[1, 1]).unwrap();
+    ///
+    /// for lengths in [&[2, 2, 2, 2][..], &[1, 2, 3, 3], &[0, 4]] {
+    ///     HuffmanTree::from_code_lengths_into(lengths, &mut scratch, &mut tree).unwrap();
+    ///     let fresh = HuffmanTree::from_code_lengths(lengths).unwrap();
+    ///     assert_eq!(tree.lookup, fresh.lookup);
+    ///     assert_eq!(tree.max_bits, fresh.max_bits);
+    ///     assert_eq!(tree.code_lengths, lengths);
+    /// }
+    /// ```
+    pub fn from_code_lengths_into(
+        code_lengths: &[u8],
+        scratch: &mut HuffmanScratch,
+        tree: &mut HuffmanTree,
+    ) -> Result<(), HuffmanError> {
+        tree.max_bits = build_lookup(code_lengths, scratch, &mut tree.lookup)?;
+        tree.alphabet_size = code_lengths.len();
+        tree.code_lengths.clear();
+        tree.code_lengths.extend_from_slice(code_lengths);
 
-        Ok(HuffmanTree { lookup, max_bits })
+        Ok(())
     }
 
     /// Decode a symbol from the bitstream using the lookup table.
@@ -112,23 +277,78 @@ impl HuffmanTree {
     /// # Errors
     ///
     /// * Returns `HuffmanError::IncompleteTree` if the tree is incomplete.
+    /// * Returns `HuffmanError::SymbolOutOfRange` if the decoded symbol is not below
+    ///   `alphabet_size`.
     /// * Returns `HuffmanError::BitReaderError` if there is an error reading bits.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use brotschneider::{BitReader, HuffmanTree};
+    /// use brotschneider::{BitReader, HuffmanError, HuffmanTree};
     ///
     /// let code_lengths = [2, 2, 2, 2]; // Complete tree
-    /// let tree = HuffmanTree::from_code_lengths(&code_lengths).unwrap();
+    /// let mut tree = HuffmanTree::from_code_lengths(&code_lengths).unwrap();
     ///
     /// let mut reader = BitReader::new(&[0b00000000]);
     /// let symbol = tree.decode_symbol(&mut reader).unwrap();
     /// assert_eq!(symbol, 0);
+    ///
+    /// // A tree built from more lengths than its alphabet has symbols is caught on decode.
+    /// tree.alphabet_size = 3;
+    /// let mut reader = BitReader::new(&[0b1100_0000]);
+    /// assert!(matches!(
+    ///     tree.decode_symbol(&mut reader),
+    ///     Err(HuffmanError::SymbolOutOfRange(3))
+    /// ));
+    /// ```
+    ///
+    /// Near the end of the input, fewer than `max_bits` bits may remain. A code that fits
+    /// in the remaining bits still decodes:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitReaderError, HuffmanError, HuffmanTree};
+    ///
+    /// // Symbol 0 is coded as 0, symbol 1 as 10 and symbol 2 as 11.
+    /// let tree = HuffmanTree::from_code_lengths(&[1, 2, 2]).unwrap();
+    ///
+    /// // The last symbol is the 1-bit code in the last bit of the input.
+    /// let mut reader = BitReader::new(&[0b10_11_10_0_0]);
+    /// let symbols: Vec<u16> = (0..5).map(|_| tree.decode_symbol(&mut reader).unwrap()).collect();
+    /// assert_eq!(symbols, [1, 2, 1, 0, 0]);
+    /// assert!(reader.is_empty());
+    ///
+    /// // A 2-bit code cut off after its first bit is still an error.
+    /// let mut reader = BitReader::new(&[0b10_11_10_0_1]);
+    /// for _ in 0..4 {
+    ///     tree.decode_symbol(&mut reader).unwrap();
+    /// }
+    /// assert!(matches!(
+    ///     tree.decode_symbol(&mut reader),
+    ///     Err(HuffmanError::BitReaderError(BitReaderError::UnexpectedEndOfInput))
+    /// ));
+    /// assert_eq!(reader.bits_remaining(), 1);
     /// ```
     pub fn decode_symbol(&self, reader: &mut BitReader) -> Result<u16, HuffmanError> {
-        let bits = reader.peek_bits(self.max_bits)? as usize;
-        let symbol = self.lookup[bits];
+        if self.max_bits == 0 {
+            return self.check_range(self.lookup_entry(0)?);
+        }
+
+        let (bits, available) = match reader.peek_bits(self.max_bits) {
+            Ok(bits) => (bits as usize, self.max_bits),
+            // A code shorter than `max_bits` may still fit in the bits left; the missing
+            // bits are taken as zero and any code that needs them is rejected below.
+            Err(BitReaderError::UnexpectedEndOfInput) => {
+                let available = reader.bits_remaining().min(self.max_bits as usize) as u8;
+                if available == 0 {
+                    return Err(BitReaderError::UnexpectedEndOfInput.into());
+                }
+
+                let bits = reader.peek_bits(available)? as usize;
+                (bits << (self.max_bits - available), available)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let symbol = self.lookup_entry(bits)?;
 
         if symbol == 0xffff {
             return Err(HuffmanError::IncompleteTree);
@@ -141,15 +361,19 @@ impl HuffmanTree {
             let mut match_all = true;
 
             for i in 0..fill_count {
-                if self.lookup[idx | i] != symbol {
+                if self.lookup_entry(idx | i)? != symbol {
                     match_all = false;
                     break;
                 }
             }
 
             if match_all {
+                if code_len > available {
+                    return Err(BitReaderError::UnexpectedEndOfInput.into());
+                }
+
                 reader.skip_bits(code_len as usize)?;
-                return Ok(symbol);
+                return self.check_range(symbol);
             }
 
             code_len += 1;
@@ -157,4 +381,1121 @@ impl HuffmanTree {
 
         Err(HuffmanError::IncompleteTree)
     }
+
+    /// Decode a symbol from the bitstream without validating the tree or the input.
+    ///
+    /// This skips the sentinel, bounds and alphabet checks of `decode_symbol` and takes the
+    /// code length of the decoded symbol from `code_lengths` instead of searching the
+    /// lookup table for it. Use it only for trusted trees in hot loops; `decode_symbol` is
+    /// the safe default.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    ///
+    /// # Returns
+    ///
+    /// * The decoded symbol.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that:
+    ///
+    /// * the tree was built by `from_code_lengths` (or another constructor of this type)
+    ///   and `validate_complete` succeeds on it, and none of its public fields were modified
+    ///   afterwards, so that every entry of `lookup` is a symbol below `code_lengths.len()`;
+    /// * at least `max_bits` bits remain in `reader`.
+    ///
+    /// Breaking either condition is undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, HuffmanTree};
+    ///
+    /// let tree = HuffmanTree::from_code_lengths(&[1, 2, 3, 3]).unwrap();
+    /// tree.validate_complete().unwrap();
+    ///
+    /// let data = [0b0_10_110_11, 0b1_0000000];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// let mut symbols = Vec::new();
+    /// while reader.bits_remaining() >= tree.max_bits as usize {
+    ///     // SAFETY: the tree is complete and unmodified, and `max_bits` bits remain.
+    ///     symbols.push(unsafe { tree.decode_symbol_unchecked(&mut reader) });
+    /// }
+    /// assert_eq!(symbols[..4], [0, 1, 2, 3]);
+    /// ```
+    #[cfg(feature = "fast-decode")]
+    #[inline]
+    pub unsafe fn decode_symbol_unchecked(&self, reader: &mut BitReader) -> u16 {
+        if self.max_bits == 0 {
+            // SAFETY: a single-symbol tree has a one-entry lookup table.
+            return unsafe { *self.lookup.get_unchecked(0) };
+        }
+
+        // SAFETY: the caller guarantees that `max_bits` bits remain.
+        let bits = unsafe { reader.peek_bits(self.max_bits).unwrap_unchecked() };
+        // SAFETY: the lookup table of a complete tree has 2^max_bits entries, each a symbol
+        // with a code length.
+        let (symbol, len) = unsafe {
+            let symbol = *self.lookup.get_unchecked(bits as usize);
+            (symbol, *self.code_lengths.get_unchecked(symbol as usize))
+        };
+        // SAFETY: the code is no longer than `max_bits`, which remain.
+        unsafe { reader.skip_bits(len as usize).unwrap_unchecked() };
+
+        symbol
+    }
+
+    /// Returns the lookup table entry at `idx`, or `Corrupt` if the index is out of range.
+    #[inline]
+    fn lookup_entry(&self, idx: usize) -> Result<u16, HuffmanError> {
+        self.lookup.get(idx).copied().ok_or(HuffmanError::Corrupt {
+            reason: "lookup index out of range",
+        })
+    }
+
+    /// Returns `symbol` if it is within the alphabet, or `SymbolOutOfRange` otherwise.
+    #[inline]
+    fn check_range(&self, symbol: u16) -> Result<u16, HuffmanError> {
+        if symbol as usize >= self.alphabet_size {
+            return Err(HuffmanError::SymbolOutOfRange(symbol));
+        }
+
+        Ok(symbol)
+    }
+
+    /// Decode a symbol, charging the bits it consumes against a budget.
+    ///
+    /// This bounds the work spent on an untrusted stream independently of how much output it
+    /// produces. The budget is shared across calls, so pass the same counter for every symbol
+    /// of a stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `budget` - The number of bits that may still be consumed; reduced by the bits read.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decoded symbol.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `HuffmanError::BudgetExceeded` if the symbol's code is longer than the
+    ///   remaining budget. The reader has still consumed the code in that case.
+    /// * Returns the same errors as `HuffmanTree::decode_symbol`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, HuffmanError, HuffmanTree};
+    ///
+    /// let tree = HuffmanTree::from_code_lengths(&[2, 2, 2, 2]).unwrap();
+    /// let mut reader = BitReader::new(&[0b0110_1100]);
+    /// let mut budget = 5;
+    ///
+    /// assert_eq!(tree.decode_symbol_with_budget(&mut reader, &mut budget).unwrap(), 1);
+    /// assert_eq!(tree.decode_symbol_with_budget(&mut reader, &mut budget).unwrap(), 2);
+    /// assert_eq!(budget, 1);
+    /// assert!(matches!(
+    ///     tree.decode_symbol_with_budget(&mut reader, &mut budget),
+    ///     Err(HuffmanError::BudgetExceeded)
+    /// ));
+    /// ```
+    pub fn decode_symbol_with_budget(
+        &self,
+        reader: &mut BitReader,
+        budget: &mut usize,
+    ) -> Result<u16, HuffmanError> {
+        let start = reader.bit_offset();
+        let symbol = self.decode_symbol(reader)?;
+        let consumed = reader.bit_offset() - start;
+
+        *budget = budget
+            .checked_sub(consumed)
+            .ok_or(HuffmanError::BudgetExceeded)?;
+
+        Ok(symbol)
+    }
+
+    /// Decode a symbol and count it in a histogram indexed by symbol.
+    ///
+    /// Decoding a block's symbols through this method collects their actual distribution,
+    /// which can be compared against the code lengths to study coding efficiency. The
+    /// histogram grows as needed to hold the decoded symbol.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `histogram` - The symbol counts to update.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the decoded symbol.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `HuffmanTree::decode_symbol`; the histogram is not
+    ///   updated on error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, HuffmanTree};
+    ///
+    /// let tree = HuffmanTree::from_code_lengths(&[1, 2, 2]).unwrap();
+    /// let mut reader = BitReader::new(&[0b0010_1100]);
+    /// let mut histogram = Vec::new();
+    ///
+    /// for _ in 0..5 {
+    ///     tree.decode_with_histogram(&mut reader, &mut histogram).unwrap();
+    /// }
+    /// assert_eq!(histogram, [3, 1, 1]);
+    /// ```
+    pub fn decode_with_histogram(
+        &self,
+        reader: &mut BitReader,
+        histogram: &mut Vec<u32>,
+    ) -> Result<u16, HuffmanError> {
+        let symbol = self.decode_symbol(reader)?;
+
+        let index = symbol as usize;
+        if histogram.len() <= index {
+            histogram.resize(index + 1, 0);
+        }
+        histogram[index] += 1;
+
+        Ok(symbol)
+    }
+
+    /// Returns the canonical code of a symbol, for encoding it with `BitWriter::write_bits`.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The symbol to look up.
+    ///
+    /// # Returns
+    ///
+    /// * The `(code, length)` of the symbol, or `None` if the symbol is not in the tree. The
+    ///   only symbol of a single-symbol tree has a code of length 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, HuffmanTree};
+    ///
+    /// let tree = HuffmanTree::from_code_lengths(&[2, 1, 3, 0, 3]).unwrap();
+    /// assert_eq!(tree.code_for(1), Some((0b0, 1)));
+    /// assert_eq!(tree.code_for(4), Some((0b111, 3)));
+    /// assert_eq!(tree.code_for(3), None);
+    ///
+    /// let message = [0, 4, 1, 1, 2, 0];
+    /// let mut writer = BitWriter::new();
+    /// for &symbol in &message {
+    ///     let (code, len) = tree.code_for(symbol).unwrap();
+    ///     writer.write_bits(code, len).unwrap();
+    /// }
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// for &symbol in &message {
+    ///     assert_eq!(tree.decode_symbol(&mut reader).unwrap(), symbol);
+    /// }
+    /// ```
+    pub fn code_for(&self, symbol: u16) -> Option<(u32, u8)> {
+        if self.max_bits == 0 {
+            return (self.lookup.first() == Some(&symbol)).then_some((0, 0));
+        }
+
+        // Canonical codes fill a contiguous, aligned run of 2^(max_bits - len) entries.
+        let first = self.lookup.iter().position(|&s| s == symbol)?;
+        let run = self.lookup[first..]
+            .iter()
+            .take_while(|&&s| s == symbol)
+            .count();
+        let spare_bits = run.trailing_zeros() as u8;
+
+        Some(((first >> spare_bits) as u32, self.max_bits - spare_bits))
+    }
+
+    /// Check whether every entry of the lookup table maps to a symbol.
+    ///
+    /// Trees built by `from_code_lengths` are always complete; this is for trees whose
+    /// lookup table was assembled or modified by other means.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the lookup table has no unassigned entries, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::HuffmanTree;
+    ///
+    /// let mut tree = HuffmanTree::from_code_lengths(&[1, 2, 2]).unwrap();
+    /// assert!(tree.is_complete());
+    ///
+    /// tree.lookup[3] = 0xffff;
+    /// assert!(!tree.is_complete());
+    /// ```
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        !self.lookup.is_empty() && !self.lookup.contains(&0xffff)
+    }
+
+    /// Check that the lookup table agrees with the stored code lengths.
+    ///
+    /// The canonical code of every symbol is recomputed from `code_lengths`, and each lookup
+    /// entry its code covers must map back to the symbol. Every entry must be covered by
+    /// exactly one symbol. This is a debugging aid for code that builds or modifies lookup
+    /// tables.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the lookup table matches the code lengths.
+    /// * `Err(HuffmanError)` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `HuffmanError::Corrupt` if an entry disagrees with the code lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{HuffmanError, HuffmanTree};
+    ///
+    /// let mut tree = HuffmanTree::from_code_lengths(&[2, 1, 3, 0, 3]).unwrap();
+    /// assert!(tree.self_check().is_ok());
+    ///
+    /// // Symbol 1 has code 0, which covers entries 0-3 of the 3-bit table.
+    /// tree.lookup[3] = 2;
+    /// assert!(matches!(tree.self_check(), Err(HuffmanError::Corrupt { .. })));
+    ///
+    /// let tree = HuffmanTree::from_code_lengths(&[0, 0, 7]).unwrap();
+    /// assert!(tree.self_check().is_ok());
+    /// ```
+    pub fn self_check(&self) -> Result<(), HuffmanError> {
+        let mismatch = HuffmanError::Corrupt {
+            reason: "lookup table disagrees with code lengths",
+        };
+
+        let mut used = self
+            .code_lengths
+            .iter()
+            .enumerate()
+            .filter(|&(_, &len)| len != 0);
+        if self.max_bits == 0 {
+            return match (used.next(), used.next()) {
+                (Some((symbol, _)), None) if self.lookup == [symbol as u16] => Ok(()),
+                _ => Err(mismatch),
+            };
+        }
+
+        if self.lookup.len() != 1 << self.max_bits {
+            return Err(mismatch);
+        }
+
+        let codes = canonical_codes(&self.code_lengths);
+        let mut covered = 0usize;
+        for (symbol, &len) in used {
+            let Some(spare_bits) = self.max_bits.checked_sub(len) else {
+                return Err(mismatch);
+            };
+
+            let first = (codes[symbol] as usize) << spare_bits;
+            let entries = self.lookup.get(first..first + (1 << spare_bits));
+            if !entries.is_some_and(|entries| entries.iter().all(|&s| s as usize == symbol)) {
+                return Err(mismatch);
+            }
+
+            covered += 1 << spare_bits;
+        }
+
+        if covered != self.lookup.len() {
+            return Err(mismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Check that the tree is complete, for decode paths that require it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the tree is complete.
+    /// * `Err(HuffmanError)` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `HuffmanError::IncompleteTree` if the lookup table has unassigned entries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{HuffmanError, HuffmanTree};
+    ///
+    /// let mut tree = HuffmanTree::from_code_lengths(&[1, 1]).unwrap();
+    /// assert!(tree.validate_complete().is_ok());
+    ///
+    /// tree.lookup[0] = 0xffff;
+    /// assert!(matches!(tree.validate_complete(), Err(HuffmanError::IncompleteTree)));
+    /// ```
+    #[inline]
+    pub fn validate_complete(&self) -> Result<(), HuffmanError> {
+        if !self.is_complete() {
+            return Err(HuffmanError::IncompleteTree);
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the tree is the identity code over bytes.
+    ///
+    /// Such a tree assigns every one of the 256 symbols an 8-bit code equal to the symbol
+    /// itself, so decoding a symbol is the same as reading a byte.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if every 8-bit code decodes to its own value, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::HuffmanTree;
+    ///
+    /// let tree = HuffmanTree::from_code_lengths(&[8; 256]).unwrap();
+    /// assert!(tree.is_identity());
+    ///
+    /// let tree = HuffmanTree::from_code_lengths(&[2, 2, 2, 2]).unwrap();
+    /// assert!(!tree.is_identity());
+    /// ```
+    pub fn is_identity(&self) -> bool {
+        self.max_bits == 8
+            && self.lookup.len() == 256
+            && self
+                .lookup
+                .iter()
+                .enumerate()
+                .all(|(code, &symbol)| code == symbol as usize)
+    }
+
+    /// Read a prefix code definition (simple or complex) and build its tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `alphabet_size` - The number of symbols in the alphabet the code is defined over.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the constructed HuffmanTree or an error if the code is invalid.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the errors of `read_code_lengths`.
+    /// * Returns the errors of `HuffmanTree::from_code_lengths` if the tree cannot be built.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, HuffmanTree};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_simple_code(&[7, 3], 10).unwrap();
+    /// writer.write_bits(0b10, 2).unwrap(); // symbols 7 (code 1) then 3 (code 0)
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// let tree = HuffmanTree::read(&mut reader, 10).unwrap();
+    /// assert_eq!(tree.decode_symbol(&mut reader).unwrap(), 7);
+    /// assert_eq!(tree.decode_symbol(&mut reader).unwrap(), 3);
+    /// ```
+    pub fn read(reader: &mut BitReader, alphabet_size: usize) -> Result<Self, HuffmanError> {
+        let lengths = read_code_lengths(reader, alphabet_size)?;
+
+        let mut tree = HuffmanTree::from_code_lengths(&lengths)?;
+        tree.alphabet_size = alphabet_size;
+
+        Ok(tree)
+    }
+}
+
+/// Longest code length allowed for the prefix codes of a stream.
+pub const MAX_CODE_LENGTH: u8 = 15;
+
+/// Number of symbols in the code length code alphabet.
+pub const CODE_LENGTH_CODES: usize = 18;
+
+/// Order in which the code length code lengths are stored in a complex prefix code.
+pub(crate) const CODE_LENGTH_CODE_ORDER: [usize; CODE_LENGTH_CODES] =
+    [1, 2, 3, 4, 0, 5, 17, 6, 16, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+/// Fixed prefix code for the code length code lengths, as `(code, length)` indexed by value.
+pub(crate) const CODE_LENGTH_CODE_LENGTH_CODES: [(u32, u8); 6] = [
+    (0b00, 2),
+    (0b1110, 4),
+    (0b110, 3),
+    (0b01, 2),
+    (0b10, 2),
+    (0b1111, 4),
+];
+
+/// Code length symbol that repeats the previous non-zero code length.
+pub(crate) const REPEAT_PREVIOUS_CODE_LENGTH: u8 = 16;
+
+/// Code length symbol that repeats a zero code length.
+pub(crate) const REPEAT_ZERO_CODE_LENGTH: u8 = 17;
+
+/// Code length repeated by code 16 before any non-zero code length has been seen.
+pub(crate) const INITIAL_REPEATED_CODE_LENGTH: u8 = 8;
+
+/// Returns the longest code length in a table, without building a tree.
+///
+/// This is the `max_bits` that `HuffmanTree::from_code_lengths` would produce for a table
+/// with two or more used symbols, so it can size a `peek_bits` call or a lookup buffer up
+/// front.
+///
+/// # Arguments
+///
+/// * `code_lengths` - The code length of each symbol, 0 for unused symbols.
+///
+/// # Returns
+///
+/// * The largest code length, or 0 if every length is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::huffman::max_code_length;
+///
+/// assert_eq!(max_code_length(&[1, 2, 3, 3]), 3);
+/// assert_eq!(max_code_length(&[0, 15, 0, 1]), 15);
+/// assert_eq!(max_code_length(&[0, 0]), 0);
+/// assert_eq!(max_code_length(&[]), 0);
+/// ```
+#[inline]
+pub fn max_code_length(code_lengths: &[u8]) -> u8 {
+    code_lengths.iter().copied().max().unwrap_or(0)
+}
+
+/// Fill `lookup` with the canonical code table for `code_lengths` and return the longest
+/// code length.
+///
+/// `lookup` is cleared and resized rather than reallocated, so its capacity is reused.
+fn build_lookup(
+    code_lengths: &[u8],
+    scratch: &mut HuffmanScratch,
+    lookup: &mut Vec<u16>,
+) -> Result<u8, HuffmanError> {
+    let max_bits = max_code_length(code_lengths);
+    if max_bits == 0 {
+        return Err(HuffmanError::EmptyAlphabet);
+    }
+
+    // Longer codes are invalid, and would make the lookup table grow to 2^max_bits entries.
+    if max_bits > MAX_CODE_LENGTH {
+        return Err(HuffmanError::CodeTooLong(max_bits));
+    }
+
+    let mut used = code_lengths
+        .iter()
+        .enumerate()
+        .filter(|&(_, &len)| len != 0);
+    if let (Some((symbol, _)), None) = (used.next(), used.next()) {
+        lookup.clear();
+        lookup.push(symbol as u16);
+        return Ok(0);
+    }
+
+    // A tree of depth `max_bits` has at most 2^max_bits leaves.
+    let num_used = code_lengths.iter().filter(|&&len| len != 0).count();
+    if 1usize
+        .checked_shl(max_bits as u32)
+        .is_some_and(|capacity| num_used > capacity)
+    {
+        return Err(HuffmanError::OverfullTree(max_bits));
+    }
+
+    let bl_count = &mut scratch.bl_count;
+    bl_count.clear();
+    bl_count.resize((max_bits + 1) as usize, 0);
+    for &len in code_lengths {
+        if len != 0 {
+            bl_count[len as usize] += 1;
+        }
+    }
+
+    let mut code = 0u32;
+    let next_code = &mut scratch.next_code;
+    next_code.clear();
+    next_code.resize((max_bits + 1) as usize, 0);
+
+    for bits in 1..=max_bits {
+        code = (code + bl_count[(bits - 1) as usize] as u32) << 1;
+        next_code[bits as usize] = code;
+    }
+
+    let mut total_codes = 0u32;
+
+    for bits in 1..=max_bits {
+        total_codes = (total_codes << 1) + bl_count[bits as usize] as u32;
+    }
+
+    if total_codes != (1u32 << max_bits) {
+        if total_codes > (1u32 << max_bits) {
+            return Err(HuffmanError::OverfullTree(max_bits));
+        }
+
+        return Err(HuffmanError::IncompleteTree);
+    }
+
+    let table_size = 1 << max_bits;
+    lookup.clear();
+    lookup.resize(table_size, 0xffff);
+
+    for (symbol, &len) in code_lengths.iter().enumerate() {
+        if len != 0 {
+            // Guards the shifts below; `max_bits` is the largest length by construction.
+            let Some(spare_bits) = max_bits.checked_sub(len) else {
+                return Err(HuffmanError::Corrupt {
+                    reason: "code length exceeds maximum",
+                });
+            };
+
+            let code_val = next_code[len as usize];
+            next_code[len as usize] += 1;
+
+            let prefix = code_val << spare_bits;
+            let fill_count = 1 << spare_bits;
+
+            for i in 0..fill_count {
+                let idx = (prefix | i) as usize;
+                lookup[idx] = symbol as u16;
+            }
+        }
+    }
+
+    Ok(max_bits)
+}
+
+/// Compute the canonical code for each symbol from its code length.
+///
+/// Symbols with a code length of 0 are assigned a code of 0.
+pub(crate) fn canonical_codes(code_lengths: &[u8]) -> Vec<u32> {
+    let max_bits = code_lengths.iter().copied().max().unwrap_or(0);
+
+    let mut bl_count = vec![0u32; max_bits as usize + 1];
+    for &len in code_lengths {
+        if len != 0 {
+            bl_count[len as usize] += 1;
+        }
+    }
+
+    let mut code = 0u32;
+    let mut next_code = vec![0u32; max_bits as usize + 1];
+    for bits in 1..=max_bits as usize {
+        code = (code + bl_count[bits - 1]) << 1;
+        next_code[bits] = code;
+    }
+
+    code_lengths
+        .iter()
+        .map(|&len| {
+            if len == 0 {
+                return 0;
+            }
+
+            let code = next_code[len as usize];
+            next_code[len as usize] += 1;
+            code
+        })
+        .collect()
+}
+
+/// Assign a code length of at most `max_bits` to each symbol with a non-zero frequency.
+///
+/// This follows the reference encoder: a Huffman tree is built over the used symbols, sorted
+/// by frequency with ties broken towards the higher symbol. If the tree is deeper than
+/// `max_bits`, every frequency is raised to at least a floor that doubles on each attempt
+/// until it fits. A single used symbol receives a length of 1.
+///
+/// # Arguments
+///
+/// * `frequencies` - The number of occurrences of each symbol.
+/// * `max_bits` - The longest code length allowed.
+///
+/// # Returns
+///
+/// * A Result containing the code length of each symbol, 0 for unused symbols.
+///
+/// # Errors
+///
+/// * Returns `HuffmanError::OverfullTree` if more than `2^max_bits` symbols are used.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::huffman::{MAX_CODE_LENGTH, lengths_from_frequencies};
+///
+/// let lengths = lengths_from_frequencies(&[10, 6, 2, 1, 0, 1], MAX_CODE_LENGTH).unwrap();
+/// assert_eq!(lengths, [1, 2, 3, 4, 0, 4]);
+///
+/// // Fibonacci frequencies need lengths of up to 17 bits without a limit.
+/// let mut fibonacci = vec![1u32, 1];
+/// while fibonacci.len() < 18 {
+///     fibonacci.push(fibonacci[fibonacci.len() - 1] + fibonacci[fibonacci.len() - 2]);
+/// }
+///
+/// let lengths = lengths_from_frequencies(&fibonacci, 17).unwrap();
+/// assert_eq!(lengths, [17, 17, 16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+///
+/// let lengths = lengths_from_frequencies(&fibonacci, MAX_CODE_LENGTH).unwrap();
+/// assert_eq!(lengths, [9, 9, 9, 9, 8, 8, 7, 7, 6, 6, 5, 5, 4, 4, 3, 3, 2, 2]);
+/// ```
+pub fn lengths_from_frequencies(
+    frequencies: &[u32],
+    max_bits: u8,
+) -> Result<Vec<u8>, HuffmanError> {
+    /// A node of the tree: a leaf holding a symbol, or the parent of two earlier nodes.
+    #[derive(Clone, Copy)]
+    struct Node {
+        count: u64,
+        left: Option<usize>,
+        right_or_symbol: usize,
+    }
+
+    const SENTINEL: Node = Node {
+        count: u64::MAX,
+        left: None,
+        right_or_symbol: 0,
+    };
+
+    let mut lengths = vec![0u8; frequencies.len()];
+    let num_used = frequencies.iter().filter(|&&freq| freq != 0).count();
+    match num_used {
+        0 => return Ok(lengths),
+        1 => {
+            let symbol = frequencies.iter().position(|&freq| freq != 0).unwrap_or(0);
+            lengths[symbol] = 1;
+            return Ok(lengths);
+        }
+        _ if 1usize
+            .checked_shl(max_bits as u32)
+            .is_some_and(|capacity| num_used > capacity) =>
+        {
+            return Err(HuffmanError::OverfullTree(max_bits));
+        }
+        _ => {}
+    }
+
+    let n = num_used;
+    let mut nodes = vec![SENTINEL; 2 * n + 1];
+    let mut count_limit = 1u64;
+
+    loop {
+        // Leaves occupy [0, n), sorted by count and then by descending symbol. Parents are
+        // created in ascending count order from n + 1 on, so the two smallest remaining
+        // nodes are always at the front of one of the two runs.
+        let leaves = frequencies
+            .iter()
+            .enumerate()
+            .filter(|&(_, &freq)| freq != 0)
+            .map(|(symbol, &freq)| Node {
+                count: (freq as u64).max(count_limit),
+                left: None,
+                right_or_symbol: symbol,
+            });
+        for (node, leaf) in nodes.iter_mut().zip(leaves) {
+            *node = leaf;
+        }
+        nodes[..n].sort_by(|a, b| {
+            a.count
+                .cmp(&b.count)
+                .then(b.right_or_symbol.cmp(&a.right_or_symbol))
+        });
+        nodes[n..].fill(SENTINEL);
+
+        let (mut leaf, mut parent) = (0, n + 1);
+        for k in (1..n).rev() {
+            let mut take_smallest = || {
+                if nodes[leaf].count <= nodes[parent].count {
+                    leaf += 1;
+                    leaf - 1
+                } else {
+                    parent += 1;
+                    parent - 1
+                }
+            };
+            let left = take_smallest();
+            let right = take_smallest();
+
+            nodes[2 * n - k] = Node {
+                count: nodes[left].count + nodes[right].count,
+                left: Some(left),
+                right_or_symbol: right,
+            };
+        }
+
+        let mut fits = true;
+        let mut stack = vec![(2 * n - 1, 0u8)];
+        while let Some((index, depth)) = stack.pop() {
+            let node = nodes[index];
+            match node.left {
+                Some(_) if depth >= max_bits => {
+                    fits = false;
+                    break;
+                }
+                Some(left) => {
+                    stack.push((left, depth + 1));
+                    stack.push((node.right_or_symbol, depth + 1));
+                }
+                None => lengths[node.right_or_symbol] = depth,
+            }
+        }
+
+        if fits {
+            return Ok(lengths);
+        }
+
+        count_limit *= 2;
+    }
+}
+
+/// Number of bits needed to encode a symbol of an alphabet with `alphabet_size` symbols.
+pub(crate) fn alphabet_bits(alphabet_size: usize) -> u8 {
+    (usize::BITS - alphabet_size.saturating_sub(1).leading_zeros()) as u8
+}
+
+/// Read a prefix code definition (simple or complex) and return the code length of each
+/// symbol, without building a tree.
+///
+/// This is what `HuffmanTree::read` parses before building its lookup table, so it shows
+/// exactly which lengths the stream encoded.
+///
+/// # Arguments
+///
+/// * `reader` - A mutable reference to a `BitReader` instance.
+/// * `alphabet_size` - The number of symbols in the alphabet.
+///
+/// # Returns
+///
+/// * A Result containing the code length for each of the `alphabet_size` symbols.
+///
+/// # Errors
+///
+/// * Returns the errors of `read_simple` or `read_complex`, depending on the code type.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::huffman::read_code_lengths;
+/// use brotschneider::{BitReader, BitWriter, HuffmanTree};
+///
+/// let mut writer = BitWriter::new();
+/// writer.write_simple_code(&[2, 0, 1], 4).unwrap();
+/// writer.write_complex_code(&[1, 2, 3, 3]).unwrap();
+/// let data = writer.into_inner();
+///
+/// let mut reader = BitReader::new(&data);
+/// assert_eq!(read_code_lengths(&mut reader, 4).unwrap(), [2, 2, 1, 0]);
+///
+/// let lengths = read_code_lengths(&mut reader, 4).unwrap();
+/// assert_eq!(lengths, [1, 2, 3, 3]);
+/// assert!(HuffmanTree::from_code_lengths(&lengths).unwrap().is_complete());
+/// ```
+pub fn read_code_lengths(
+    reader: &mut BitReader,
+    alphabet_size: usize,
+) -> Result<Vec<u8>, HuffmanError> {
+    if reader.peek_bits(2)? == 1 {
+        read_simple(reader, alphabet_size)
+    } else {
+        read_complex(reader, alphabet_size)
+    }
+}
+
+/// Read a simple prefix code and return the code length of each symbol.
+///
+/// A simple prefix code lists one to four symbols explicitly. A single symbol is reported with
+/// a code length of 1 and is decoded without consuming any bits.
+///
+/// # Arguments
+///
+/// * `reader` - A mutable reference to a `BitReader` instance.
+/// * `alphabet_size` - The number of symbols in the alphabet.
+///
+/// # Returns
+///
+/// * A Result containing the code length for each of the `alphabet_size` symbols.
+///
+/// # Errors
+///
+/// * Returns `HuffmanError::Corrupt` if the stream holds a complex prefix code, or a symbol is
+///   out of range for the alphabet or listed twice.
+/// * Returns `HuffmanError::BitReaderError` if there is an error reading bits.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::huffman::read_simple;
+/// use brotschneider::{BitReader, BitWriter, HuffmanError};
+///
+/// // A distance alphabet with NPOSTFIX = 0 and NDIRECT = 1 has 65 symbols.
+/// let mut writer = BitWriter::new();
+/// writer.write_simple_code(&[64, 0, 5], 65).unwrap();
+/// let data = writer.into_inner();
+///
+/// let mut reader = BitReader::new(&data);
+/// let lengths = read_simple(&mut reader, 65).unwrap();
+/// assert_eq!((lengths[64], lengths[0], lengths[5]), (1, 2, 2));
+///
+/// // Symbol 65 fits in the 7-bit symbol field but is outside the alphabet.
+/// let mut writer = BitWriter::new();
+/// writer.write_bits(0b01, 2).unwrap(); // HSKIP = 1 (simple)
+/// writer.write_bits(1, 2).unwrap(); // NSYM = 2
+/// writer.write_bits(3, 7).unwrap();
+/// writer.write_bits(65, 7).unwrap();
+/// let data = writer.into_inner();
+///
+/// let mut reader = BitReader::new(&data);
+/// assert!(matches!(
+///     read_simple(&mut reader, 65),
+///     Err(HuffmanError::Corrupt { .. })
+/// ));
+/// ```
+pub fn read_simple(reader: &mut BitReader, alphabet_size: usize) -> Result<Vec<u8>, HuffmanError> {
+    if reader.read_bits(2)? != 1 {
+        return Err(HuffmanError::Corrupt {
+            reason: "expected a simple prefix code",
+        });
+    }
+
+    let num_symbols = reader.read_bits(2)? as usize + 1;
+    let bits = alphabet_bits(alphabet_size);
+
+    let mut symbols = [0usize; 4];
+    for i in 0..num_symbols {
+        let symbol = reader.read_bits(bits)? as usize;
+        if symbol >= alphabet_size {
+            return Err(HuffmanError::Corrupt {
+                reason: "symbol out of range for alphabet",
+            });
+        }
+
+        if symbols[..i].contains(&symbol) {
+            return Err(HuffmanError::Corrupt {
+                reason: "duplicate symbol in simple prefix code",
+            });
+        }
+
+        symbols[i] = symbol;
+    }
+
+    let code_lengths: &[u8] = match num_symbols {
+        1 => &[1],
+        2 => &[1, 1],
+        3 => &[1, 2, 2],
+        _ if reader.read_bits(1)? == 0 => &[2, 2, 2, 2],
+        _ => &[1, 2, 3, 3],
+    };
+
+    let mut lengths = vec![0u8; alphabet_size];
+    for (&symbol, &len) in symbols.iter().zip(code_lengths) {
+        lengths[symbol] = len;
+    }
+
+    Ok(lengths)
+}
+
+/// Read a complex prefix code and return the code length of each symbol.
+///
+/// This reads the HSKIP field, the code length code lengths, and the run-length encoded
+/// symbol code lengths. Symbols beyond the last encoded code length have a length of 0.
+///
+/// Code length symbols 0 to 15 are lengths, and 16 and 17 repeat an earlier length or
+/// zero, so every returned length is at most `MAX_CODE_LENGTH` whatever the input.
+///
+/// # Arguments
+///
+/// * `reader` - A mutable reference to a `BitReader` instance.
+/// * `alphabet_size` - The number of symbols in the alphabet.
+///
+/// # Returns
+///
+/// * A Result containing the code length for each of the `alphabet_size` symbols.
+///
+/// # Errors
+///
+/// * Returns `HuffmanError::Corrupt` if the stream holds a simple prefix code, the code length
+///   code is invalid, or a repeat code runs past the end of the alphabet.
+/// * Returns `HuffmanError::IncompleteTree` if the code lengths do not form a complete tree.
+/// * Returns `HuffmanError::OverfullTree` if the code lengths oversubscribe the tree.
+/// * Returns `HuffmanError::BitReaderError` if there is an error reading bits.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::huffman::read_complex;
+/// use brotschneider::{BitReader, BitWriter};
+///
+/// let lengths = [2, 2, 2, 2];
+/// let mut writer = BitWriter::new();
+/// writer.write_complex_code(&lengths).unwrap();
+/// let data = writer.into_inner();
+///
+/// let mut reader = BitReader::new(&data);
+/// assert_eq!(read_complex(&mut reader, 4).unwrap(), lengths);
+/// ```
+///
+/// Arbitrary input never yields a length over 15:
+///
+/// ```rust
+/// use brotschneider::huffman::{MAX_CODE_LENGTH, read_complex};
+/// use brotschneider::BitReader;
+///
+/// let mut state = 1u32;
+/// for _ in 0..2000 {
+///     let data: Vec<u8> = (0..64)
+///         .map(|_| {
+///             state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+///             (state >> 24) as u8 & 0b1111_1100 // HSKIP = 0
+///         })
+///         .collect();
+///
+///     if let Ok(lengths) = read_complex(&mut BitReader::new(&data), 704) {
+///         assert!(lengths.iter().all(|&len| len <= MAX_CODE_LENGTH));
+///     }
+/// }
+/// ```
+pub fn read_complex(reader: &mut BitReader, alphabet_size: usize) -> Result<Vec<u8>, HuffmanError> {
+    let hskip = reader.read_bits(2)? as usize;
+    if hskip == 1 {
+        return Err(HuffmanError::Corrupt {
+            reason: "expected a complex prefix code",
+        });
+    }
+
+    let mut code_length_code_lengths = [0u8; CODE_LENGTH_CODES];
+    let mut space = 32i32;
+    let mut num_codes = 0;
+
+    for &symbol in &CODE_LENGTH_CODE_ORDER[hskip..] {
+        let len = read_code_length_code_length(reader)?;
+        code_length_code_lengths[symbol] = len;
+
+        if len != 0 {
+            space -= 32 >> len;
+            num_codes += 1;
+
+            if space <= 0 {
+                break;
+            }
+        }
+    }
+
+    if num_codes != 1 && space != 0 {
+        return Err(HuffmanError::Corrupt {
+            reason: "invalid code length code",
+        });
+    }
+
+    // A single code length code is encoded with zero bits.
+    let code_length_tree = if num_codes == 1 {
+        None
+    } else {
+        Some(HuffmanTree::from_code_lengths(&code_length_code_lengths)?)
+    };
+    let single_code = code_length_code_lengths
+        .iter()
+        .position(|&len| len != 0)
+        .unwrap_or(0) as u8;
+
+    let mut lengths = vec![0u8; alphabet_size];
+    let mut symbol = 0usize;
+    let mut space = 32768i32;
+    let mut prev_code_len = INITIAL_REPEATED_CODE_LENGTH;
+    let mut repeat = 0usize;
+    let mut repeat_code_len = 0u8;
+
+    while symbol < alphabet_size && space > 0 {
+        let code = match &code_length_tree {
+            Some(tree) => tree.decode_symbol(reader)? as u8,
+            None => single_code,
+        };
+
+        if code < REPEAT_PREVIOUS_CODE_LENGTH {
+            repeat = 0;
+            lengths[symbol] = code;
+            symbol += 1;
+
+            if code != 0 {
+                prev_code_len = code;
+                space -= 32768 >> code;
+            }
+
+            continue;
+        }
+
+        let (extra_bits, new_len) = if code == REPEAT_PREVIOUS_CODE_LENGTH {
+            (2, prev_code_len)
+        } else {
+            (3, 0)
+        };
+        let extra = reader.read_bits(extra_bits)? as usize;
+
+        if repeat_code_len != new_len {
+            repeat = 0;
+            repeat_code_len = new_len;
+        }
+
+        let old_repeat = repeat;
+        if repeat > 0 {
+            repeat = (repeat - 2) << extra_bits;
+        }
+        repeat += extra + 3;
+
+        let delta = repeat - old_repeat;
+        if symbol + delta > alphabet_size {
+            return Err(HuffmanError::Corrupt {
+                reason: "repeated code lengths exceed alphabet size",
+            });
+        }
+
+        lengths[symbol..symbol + delta].fill(new_len);
+        symbol += delta;
+
+        if new_len != 0 {
+            space -= (delta as i32) << (15 - new_len);
+        }
+    }
+
+    if space < 0 {
+        return Err(HuffmanError::OverfullTree(prev_code_len));
+    }
+
+    if space > 0 {
+        return Err(HuffmanError::IncompleteTree);
+    }
+
+    Ok(lengths)
+}
+
+/// Read a single code length code length using the fixed prefix code.
+fn read_code_length_code_length(reader: &mut BitReader) -> Result<u8, HuffmanError> {
+    let value = match reader.read_bits(2)? {
+        0b00 => 0,
+        0b01 => 3,
+        0b10 => 4,
+        _ => match reader.read_bits(1)? {
+            0 => 2,
+            _ => match reader.read_bits(1)? {
+                0 => 1,
+                _ => 5,
+            },
+        },
+    };
+
+    Ok(value)
 }
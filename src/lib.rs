@@ -1,11 +1,44 @@
 pub mod bitreader;
 pub mod bitwriter;
+pub mod block;
+pub mod command;
+pub mod context;
+pub mod crc32;
+pub mod decoder;
+pub mod dictionary;
+pub mod distance;
 pub mod error;
 pub mod huffman;
 pub mod metablock;
+pub mod outline;
+pub mod output;
+#[cfg(feature = "std")]
+pub mod reader;
+pub mod ringbuffer;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use bitreader::BitReader;
 pub use bitwriter::BitWriter;
-pub use error::{BitReaderError, BitWriterError};
+pub use command::Command;
+pub use context::ContextMode;
+pub use crc32::Crc32;
+#[cfg(feature = "std")]
+pub use decoder::decompress_to_writer;
+pub use decoder::{
+    Decompressor, DecompressorBuilder, decode_literals_only, decompress, decompress_all,
+    decompress_into, decompress_prefix, decompress_with_capacity, decompress_with_crc,
+    decompress_with_dictionary, is_probably_brotli, outline, output_size, peek_first_header,
+    summarize_meta_blocks,
+};
+pub use distance::DistanceRing;
+pub use error::{BitReaderError, BitWriterError, EncodeError, HuffmanError, MetaBlockError};
 pub use huffman::HuffmanTree;
-pub use metablock::{MetaBlock, MetaBlockHeader};
+pub use metablock::{
+    DecodeContext, DecodeState, MetaBlock, MetaBlockHeader, MetaBlockSummary, MetaBlockTrees,
+};
+pub use outline::{MetaBlockOutline, StreamOutline, TreeOutline};
+pub use output::{DecodeFailure, Decompressed, Recovered};
+#[cfg(feature = "std")]
+pub use reader::BrotliReader;
+pub use ringbuffer::RingBuffer;
@@ -1,57 +1,1847 @@
-use crate::error::MetaBlockError;
-use crate::{BitReader, HuffmanTree};
+use std::ops::Range;
+
+use crate::block::read_nbltypes;
+use crate::command::{Command, DistanceSource, INSERT_COPY_ALPHABET_SIZE, InsertCopy};
+use crate::context::{ContextMode, read_context_modes};
+use crate::distance::{DistanceParams, DistanceRing, NUM_DISTANCE_SHORT_CODES, SHORT_CODE_INDEX};
+use crate::error::{BitReaderError, BitWriterError, HuffmanError, MetaBlockError};
+use crate::{BitReader, BitWriter, Crc32, HuffmanTree};
+
+/// Number of symbols in the literal alphabet.
+pub(crate) const LITERAL_ALPHABET_SIZE: usize = 256;
+
+/// Number of literal contexts per literal block type.
+const LITERAL_CONTEXTS: usize = 64;
+
+/// Number of distance contexts per distance block type.
+const DISTANCE_CONTEXTS: usize = 4;
 
 #[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MetaBlockHeader {
     pub is_last: bool,
+    /// Whether this is an empty last meta-block (ISLASTEMPTY), which carries no data.
+    pub is_last_empty: bool,
+    /// The number of uncompressed bytes (MLEN), or of metadata bytes to skip (MSKIPLEN).
     pub length: u32,
     pub is_uncompressed: bool,
+    /// Whether this is a metadata meta-block, whose contents are skipped.
+    pub is_metadata: bool,
+}
+
+impl MetaBlockHeader {
+    /// Read the header of a meta-block (ISLAST, ISLASTEMPTY, MNIBBLES, MLEN, ISUNCOMPRESSED).
+    ///
+    /// For a metadata meta-block, the reader is left before the padding and skipped bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the parsed header.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::Corrupt` if the header is malformed.
+    /// * Returns `MetaBlockError::NeedMoreInput` if the input ends early.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, MetaBlockError, MetaBlockHeader};
+    ///
+    /// // ISLAST = 1, ISLASTEMPTY = 0, then MNIBBLES - 4 and MLEN - 1 in that many nibbles.
+    /// fn header(mnibbles_code: u32, mlen_minus_one: u32, nibbles: u8) -> Vec<u8> {
+    ///     let mut writer = BitWriter::new();
+    ///     writer.write_bits(0b10, 2).unwrap();
+    ///     writer.write_bits(mnibbles_code, 2).unwrap();
+    ///     writer.write_bits(mlen_minus_one, nibbles * 4).unwrap();
+    ///     writer.into_inner()
+    /// }
+    ///
+    /// // The shortest and longest length of each MNIBBLES value.
+    /// for (code, nibbles, lengths) in [
+    ///     (0, 4, [1, 1 << 16]),
+    ///     (1, 5, [(1 << 16) + 1, 1 << 20]),
+    ///     (2, 6, [(1 << 20) + 1, 1 << 24]),
+    /// ] {
+    ///     for length in lengths {
+    ///         let data = header(code, length - 1, nibbles);
+    ///         let parsed = MetaBlockHeader::read(&mut BitReader::new(&data)).unwrap();
+    ///         assert_eq!(parsed.length, length);
+    ///     }
+    /// }
+    ///
+    /// // A length that fits in fewer nibbles must use them.
+    /// let data = header(1, 0xffff, 5);
+    /// assert!(matches!(
+    ///     MetaBlockHeader::read(&mut BitReader::new(&data)),
+    ///     Err(MetaBlockError::Corrupt {
+    ///         reason: "non-minimal MNIBBLES"
+    ///     })
+    /// ));
+    ///
+    /// // MNIBBLES code 3 announces a metadata meta-block, which cannot be last.
+    /// let data = header(3, 0, 0);
+    /// assert!(matches!(
+    ///     MetaBlockHeader::read(&mut BitReader::new(&data)),
+    ///     Err(MetaBlockError::Corrupt {
+    ///         reason: "metadata block marked as last"
+    ///     })
+    /// ));
+    /// ```
+    pub fn read(reader: &mut BitReader) -> Result<Self, MetaBlockError> {
+        let mut header = MetaBlockHeader {
+            is_last: reader.read_bit().map_err(|e| with_offset(reader, e))?,
+            is_last_empty: false,
+            length: 0,
+            is_uncompressed: false,
+            is_metadata: false,
+        };
+
+        if header.is_last {
+            header.is_last_empty = reader.read_bit().map_err(|e| with_offset(reader, e))?;
+            if header.is_last_empty {
+                return Ok(header);
+            }
+        }
+
+        let nibbles = match reader.read_bits(2).map_err(|e| with_offset(reader, e))? {
+            3 => 0,
+            n => n as u8 + 4,
+        };
+
+        if nibbles == 0 {
+            if header.is_last {
+                return Err(MetaBlockError::Corrupt {
+                    reason: "metadata block marked as last",
+                });
+            }
+
+            // MNIBBLES = 0 marks a metadata block and is followed by a reserved bit.
+            if reader.read_bit().map_err(|e| with_offset(reader, e))? {
+                return Err(MetaBlockError::Corrupt {
+                    reason: "reserved MNIBBLES",
+                });
+            }
+
+            let skip_bytes = reader.read_bits(2).map_err(|e| with_offset(reader, e))? as u8;
+            if skip_bytes > 0 {
+                let skip_len = reader
+                    .read_bits(skip_bytes * 8)
+                    .map_err(|e| with_offset(reader, e))?;
+                if skip_bytes > 1 && skip_len >> ((skip_bytes - 1) * 8) == 0 {
+                    return Err(MetaBlockError::Corrupt {
+                        reason: "non-minimal MSKIPBYTES",
+                    });
+                }
+
+                header.length = skip_len + 1;
+            }
+
+            header.is_metadata = true;
+            return Ok(header);
+        }
+
+        let length = reader
+            .read_bits(nibbles * 4)
+            .map_err(|e| with_offset(reader, e))?;
+        if nibbles > 4 && length >> ((nibbles - 1) * 4) == 0 {
+            return Err(MetaBlockError::Corrupt {
+                reason: "non-minimal MNIBBLES",
+            });
+        }
+
+        header.length = length + 1;
+
+        if !header.is_last {
+            header.is_uncompressed = reader.read_bit().map_err(|e| with_offset(reader, e))?;
+        }
+
+        Ok(header)
+    }
+
+    /// Write the header of a meta-block, in the order `MetaBlockHeader::read` expects.
+    ///
+    /// For a metadata meta-block, only the header is written; the caller pads to a byte
+    /// boundary and writes the skipped bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - A mutable reference to a `BitWriter` instance.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the header was written.
+    /// * `Err(BitWriterError)` if the header cannot be encoded.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `BitWriterError::InvalidMetaBlockHeader` if the length is out of range for
+    ///   the meta-block kind, or a metadata or uncompressed meta-block is marked as last.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, BitWriterError, MetaBlockHeader};
+    ///
+    /// let header = MetaBlockHeader {
+    ///     is_last: false,
+    ///     is_last_empty: false,
+    ///     length: 70_000,
+    ///     is_uncompressed: true,
+    ///     is_metadata: false,
+    /// };
+    ///
+    /// let mut writer = BitWriter::new();
+    /// header.write(&mut writer).unwrap();
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// assert_eq!(MetaBlockHeader::read(&mut reader).unwrap(), header);
+    ///
+    /// // The last meta-block has no ISUNCOMPRESSED bit, so it cannot be uncompressed.
+    /// let last = MetaBlockHeader { is_last: true, ..header };
+    /// assert_eq!(
+    ///     last.write(&mut BitWriter::new()),
+    ///     Err(BitWriterError::InvalidMetaBlockHeader)
+    /// );
+    /// ```
+    pub fn write(&self, writer: &mut BitWriter) -> Result<(), BitWriterError> {
+        writer.write_bit(self.is_last)?;
+
+        if self.is_last {
+            writer.write_bit(self.is_last_empty)?;
+            if self.is_last_empty {
+                return Ok(());
+            }
+        }
+
+        if self.is_metadata {
+            if self.is_last || self.length > 1 << 24 {
+                return Err(BitWriterError::InvalidMetaBlockHeader);
+            }
+
+            // MNIBBLES = 0 is coded as 3, followed by the reserved bit.
+            writer.write_bits(0b11, 2)?;
+            writer.write_bit(false)?;
+
+            let skip_bytes = match self.length {
+                0 => 0,
+                len => (32 - (len - 1).leading_zeros()).div_ceil(8).max(1) as u8,
+            };
+            writer.write_bits(skip_bytes as u32, 2)?;
+            if skip_bytes > 0 {
+                writer.write_bits(self.length - 1, skip_bytes * 8)?;
+            }
+
+            return Ok(());
+        }
+
+        if self.length == 0 || self.length > 1 << 24 || (self.is_last && self.is_uncompressed) {
+            return Err(BitWriterError::InvalidMetaBlockHeader);
+        }
+
+        let nibbles = (32 - (self.length - 1).leading_zeros()).div_ceil(4).max(4) as u8;
+        writer.write_bits((nibbles - 4) as u32, 2)?;
+        writer.write_bits(self.length - 1, nibbles * 4)?;
+
+        if !self.is_last {
+            writer.write_bit(self.is_uncompressed)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write `data` as uncompressed meta-blocks, then an empty last meta-block if `is_last`.
+///
+/// Each meta-block header uses the smallest MNIBBLES (4, 5 or 6) that can represent its
+/// length, and is followed by zero bits up to the next byte boundary and the bytes
+/// themselves. Data longer than the 16 MiB a meta-block can hold is split over several
+/// meta-blocks. An uncompressed meta-block cannot be the last one of a stream, so the
+/// stream is ended with a separate empty meta-block.
+///
+/// # Arguments
+///
+/// * `writer` - The writer to append the meta-blocks to.
+/// * `data` - The bytes to store. If empty, no uncompressed meta-block is written.
+/// * `is_last` - Whether to end the stream after the data.
+///
+/// # Returns
+///
+/// * `Ok(())` if the meta-blocks were written successfully.
+/// * `Err(BitWriterError)` if an error occurs.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{BitWriter, decompress, metablock::write_uncompressed_block};
+///
+/// for len in [1, 2, 1 << 16, (1 << 16) + 1, 1 << 20, (1 << 20) + 1] {
+///     let data: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+///
+///     let mut writer = BitWriter::new();
+///     writer.write_bit(false).unwrap(); // WBITS = 16
+///     write_uncompressed_block(&mut writer, &data, true).unwrap();
+///     let stream = writer.into_inner();
+///
+///     // WBITS, ISLAST and MNIBBLES take 4 bits and ISUNCOMPRESSED 1, so the aligned
+///     // header is 3 bytes with 4 nibbles and 4 bytes with 5 or 6. The empty last
+///     // meta-block takes another byte.
+///     let header = if len <= 1 << 16 { 3 } else { 4 };
+///     assert_eq!(stream.len(), header + len + 1);
+///     assert_eq!(decompress(&stream).unwrap(), data);
+/// }
+///
+/// // Meta-blocks that are not last can be followed by others.
+/// let mut writer = BitWriter::new();
+/// writer.write_bit(false).unwrap();
+/// write_uncompressed_block(&mut writer, b"abc", false).unwrap();
+/// write_uncompressed_block(&mut writer, b"", false).unwrap();
+/// write_uncompressed_block(&mut writer, b"def", true).unwrap();
+/// assert_eq!(decompress(&writer.into_inner()).unwrap(), b"abcdef");
+/// ```
+pub fn write_uncompressed_block(
+    writer: &mut BitWriter,
+    data: &[u8],
+    is_last: bool,
+) -> Result<(), BitWriterError> {
+    for chunk in data.chunks(1 << 24) {
+        MetaBlockHeader {
+            is_last: false,
+            is_last_empty: false,
+            is_metadata: false,
+            is_uncompressed: true,
+            length: chunk.len() as u32,
+        }
+        .write(writer)?;
+
+        writer.flush()?;
+        for &byte in chunk {
+            writer.write_bits(byte as u32, 8)?;
+        }
+    }
+
+    if is_last {
+        // ISLAST and ISLASTEMPTY.
+        writer.write_bits(0b11, 2)?;
+    }
+
+    Ok(())
+}
+
+/// The largest number of prefix codes for literals, insert-and-copy lengths and distances a
+/// meta-block can declare: up to 256 of each.
+pub const MAX_TREES_PER_META_BLOCK: usize = 3 * 256;
+
+/// The number of prefix codes a meta-block may declare unless configured otherwise.
+///
+/// Each code's lookup table takes up to 64 KiB, so the format maximum would let a few
+/// header bytes demand 48 MiB of tables per meta-block. This default caps them at 16 MiB,
+/// while still letting any one category declare all 256 codes it can.
+pub const DEFAULT_MAX_TREES_PER_META_BLOCK: usize = 256;
+
+/// DecodeContext holds the state that carries over from one meta-block of a stream to the
+/// next.
+#[derive(Debug, Clone)]
+pub struct DecodeContext {
+    /// The bytes backward references may reach: any dictionary or other preset history,
+    /// followed by the output decoded so far.
+    pub window: Vec<u8>,
+    /// The distance ring buffer of the stream. It carries over between meta-blocks and is
+    /// only restored to its initial distances by `reset`, at the start of a stream.
+    pub ring: DistanceRing,
+    /// The maximum number of bytes the next meta-block may produce.
+    pub max_len: usize,
+    /// Whether to bulk-copy runs of literals coded with an identity literal tree from
+    /// byte-aligned input.
+    pub fast_literals: bool,
+    /// Whether to skip copies and keep only the inserted literals. The output is then not
+    /// the decompressed data.
+    pub literals_only: bool,
+    /// The maximum number of prefix codes for literals, insert-and-copy lengths and
+    /// distances a meta-block may declare. Defaults to `DEFAULT_MAX_TREES_PER_META_BLOCK`.
+    pub max_trees_per_block: usize,
+    /// The sliding window size of the stream, `(1 << WBITS) - 16`. Distances beyond it, or
+    /// beyond the start of the window, reference the static dictionary.
+    pub window_size: usize,
+    /// The CRC-32 of the bytes written to the output so far, if it is being computed. Bytes
+    /// are added to it as they are written, and `reset` restarts it.
+    pub crc: Option<Crc32>,
+}
+
+impl DecodeContext {
+    /// Creates a new DecodeContext for the start of a stream, with no history, no output
+    /// limit and the default limit on prefix codes.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of DecodeContext.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::metablock::DEFAULT_MAX_TREES_PER_META_BLOCK;
+    /// use brotschneider::{DecodeContext, DistanceRing};
+    ///
+    /// let context = DecodeContext::new();
+    /// assert!(context.window.is_empty());
+    /// assert_eq!(context.ring, DistanceRing::new());
+    /// assert_eq!(context.max_len, usize::MAX);
+    /// assert_eq!(context.window_size, usize::MAX);
+    /// assert_eq!(context.max_trees_per_block, DEFAULT_MAX_TREES_PER_META_BLOCK);
+    /// assert_eq!(context.crc, None);
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            window: Vec::new(),
+            ring: DistanceRing::new(),
+            max_len: usize::MAX,
+            fast_literals: false,
+            literals_only: false,
+            max_trees_per_block: DEFAULT_MAX_TREES_PER_META_BLOCK,
+            window_size: usize::MAX,
+            crc: None,
+        }
+    }
+
+    /// Creates a new DecodeContext whose window starts with `history`, such as a custom
+    /// dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The bytes that precede the stream's output.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of DecodeContext.
+    #[inline]
+    pub fn with_history(history: &[u8]) -> Self {
+        Self {
+            window: history.to_vec(),
+            ..Self::new()
+        }
+    }
+
+    /// Restores the state of the start of a stream, keeping the window's allocation and the
+    /// configured options.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.ring = DistanceRing::new();
+        if let Some(crc) = &mut self.crc {
+            *crc = Crc32::new();
+        }
+    }
+}
+
+impl Default for DecodeContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[allow(dead_code)]
+#[derive(Debug, Clone)]
 pub struct MetaBlock {
     pub header: MetaBlockHeader,
-    pub literal_huffman: Option<HuffmanTree>,
-    // Future: insert/copy, distance trees
+    /// The prefix codes for literals, indexed by the literal context map.
+    pub literal_trees: Vec<HuffmanTree>,
+    /// The prefix codes for insert-and-copy length symbols, indexed by block type.
+    pub insert_copy_trees: Vec<HuffmanTree>,
+    /// The prefix codes for distance symbols, indexed by the distance context map.
+    pub distance_trees: Vec<HuffmanTree>,
+    /// The literal tree for each literal block type and context, 64 entries per block type.
+    pub literal_context_map: Vec<u8>,
+    /// The distance tree for each distance block type and context, 4 entries per block type.
+    pub distance_context_map: Vec<u8>,
+    /// Context mode for each literal block type, indexed by block type.
+    pub context_modes: Vec<ContextMode>,
+    /// The distance code parameters (NPOSTFIX and NDIRECT).
+    pub distance_params: DistanceParams,
+    /// The stream's distance ring buffer as it stood at the end of the meta-block.
+    pub distance_ring: DistanceRing,
     pub data: Vec<u8>,
+    /// Where the command loop stands.
+    state: DecodeState,
+    /// Whether runs of byte-aligned literals can be copied straight from the input.
+    identity_literals: bool,
+    /// The number of copied bytes left out of `data` in literals-only mode.
+    pub(crate) skipped: usize,
+    /// The decoded commands, if they are being recorded.
+    pub(crate) commands: Option<Vec<Command>>,
+}
+
+/// DecodeState is the position of the command loop within a meta-block.
+///
+/// The loop keeps all of its progress here rather than on the stack, so a meta-block can be
+/// decoded one `MetaBlock::step` at a time and suspended between any two steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeState {
+    /// About to read an insert-and-copy command.
+    ReadCommand,
+    /// Inserting the literals of the current command.
+    InsertLiterals {
+        /// The number of literals still to insert.
+        remaining: u32,
+        /// The copy length of the current command.
+        copy_len: u32,
+        /// Whether the current command reuses the last distance.
+        implicit_distance: bool,
+    },
+    /// About to read the distance of the current command.
+    ReadDistance {
+        /// The copy length of the current command.
+        copy_len: u32,
+    },
+    /// About to copy the bytes of the current command.
+    Copy {
+        /// The number of bytes to copy.
+        remaining: u32,
+        /// How far back to copy from.
+        distance: u32,
+    },
+    /// The meta-block is complete.
+    Done,
+}
+
+/// The position and shape of a meta-block within a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaBlockSummary {
+    /// The offset of the meta-block header in the compressed stream, in bits.
+    pub bit_offset: usize,
+    /// The number of uncompressed bytes (MLEN), or of metadata bytes skipped (MSKIPLEN).
+    pub length: u32,
+    /// Whether this is the last meta-block of the stream.
+    pub is_last: bool,
+    /// Whether the meta-block is stored uncompressed.
+    pub is_uncompressed: bool,
+    /// Whether this is a metadata meta-block.
+    pub is_metadata: bool,
+    /// The range of the decompressed output produced by the meta-block.
+    pub output_range: Range<usize>,
+}
+
+/// The prefix codes of a decoded meta-block, borrowed from it.
+#[derive(Clone, Copy)]
+pub struct MetaBlockTrees<'a> {
+    /// The prefix code for literals.
+    pub literal: Option<&'a HuffmanTree>,
+    /// The prefix code for insert-and-copy length symbols.
+    pub insert_copy: Option<&'a HuffmanTree>,
+    /// The prefix code for distance symbols.
+    pub distance: Option<&'a HuffmanTree>,
 }
 
 impl MetaBlock {
     /// Decode a single meta-block from the stream.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::Corrupt` if the meta-block header is malformed.
+    /// * Returns `MetaBlockError::NeedMoreInput` if the input ends early.
+    /// * Returns `MetaBlockError::Huffman` if a symbol cannot be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, MetaBlock, MetaBlockError};
+    ///
+    /// // ISLAST = 0, MNIBBLES = 0 (metadata) with the reserved bit set.
+    /// let data = [0b0111_0000];
+    /// let mut reader = BitReader::new(&data);
+    ///
+    /// assert!(matches!(
+    ///     MetaBlock::decode(&mut reader),
+    ///     Err(MetaBlockError::Corrupt {
+    ///         reason: "reserved MNIBBLES"
+    ///     })
+    /// ));
+    /// ```
+    ///
+    /// The commands must produce exactly MLEN bytes. A command that would run past MLEN is
+    /// rejected, as is a stream that ends before MLEN bytes have been produced:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, MetaBlock, MetaBlockError, MetaBlockHeader};
+    ///
+    /// // Each command inserts 3 literals and copies 2 bytes at distance 3. The literal code
+    /// // is one bit wide, so `commands` commands' worth of literal bits follow the trees.
+    /// fn stream(length: u32, commands: usize) -> Vec<u8> {
+    ///     let header = MetaBlockHeader {
+    ///         is_last: true,
+    ///         is_last_empty: false,
+    ///         length,
+    ///         is_uncompressed: false,
+    ///         is_metadata: false,
+    ///     };
+    ///
+    ///     let mut writer = BitWriter::new();
+    ///     header.write(&mut writer).unwrap();
+    ///     writer.write_bits(0, 13).unwrap();
+    ///     writer.write_simple_code(&[b'x' as u16, b'y' as u16], 256).unwrap();
+    ///     writer.write_simple_code(&[152], 704).unwrap(); // insert 3, copy 2
+    ///     writer.write_simple_code(&[4], 64).unwrap(); // last distance - 1 = 3
+    ///     for _ in 0..commands {
+    ///         writer.write_bits(0b010, 3).unwrap(); // literals "xyx"
+    ///     }
+    ///     writer.into_inner()
+    /// }
+    ///
+    /// // Exact fit: two commands produce 10 bytes.
+    /// let data = stream(10, 2);
+    /// let block = MetaBlock::decode(&mut BitReader::new(&data)).unwrap();
+    /// assert_eq!(block.into_data(), b"xyxxyxyxyx");
+    ///
+    /// // The insert of the last command completes the meta-block, so its copy is ignored.
+    /// let data = stream(8, 2);
+    /// let block = MetaBlock::decode(&mut BitReader::new(&data)).unwrap();
+    /// assert_eq!(block.into_data(), b"xyxxyxyx");
+    ///
+    /// // Overshoot: the copy of the second command would produce 10 bytes out of 9.
+    /// let data = stream(9, 2);
+    /// assert!(matches!(
+    ///     MetaBlock::decode(&mut BitReader::new(&data)),
+    ///     Err(MetaBlockError::Corrupt {
+    ///         reason: "copy length exceeds meta-block length"
+    ///     })
+    /// ));
+    ///
+    /// // Overshoot: the insert of the second command would produce 8 bytes out of 7.
+    /// let data = stream(7, 2);
+    /// assert!(matches!(
+    ///     MetaBlock::decode(&mut BitReader::new(&data)),
+    ///     Err(MetaBlockError::Corrupt {
+    ///         reason: "insert length exceeds meta-block length"
+    ///     })
+    /// ));
+    ///
+    /// // Undershoot: the stream runs out of commands after 5 of 10 bytes.
+    /// let data = stream(10, 1);
+    /// assert!(matches!(
+    ///     MetaBlock::decode(&mut BitReader::new(&data)),
+    ///     Err(MetaBlockError::NeedMoreInput { .. })
+    /// ));
+    /// ```
+    ///
+    /// The smallest meta-block holds a single byte, coded as MLEN - 1 = 0:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, MetaBlock, MetaBlockHeader};
+    ///
+    /// let header = MetaBlockHeader {
+    ///     is_last: true,
+    ///     is_last_empty: false,
+    ///     length: 1,
+    ///     is_uncompressed: false,
+    ///     is_metadata: false,
+    /// };
+    ///
+    /// let mut writer = BitWriter::new();
+    /// header.write(&mut writer).unwrap();
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'z' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[8], 704).unwrap(); // insert 1, copy 2
+    /// writer.write_simple_code(&[0], 64).unwrap();
+    /// let data = writer.into_inner();
+    ///
+    /// // The insert completes the meta-block, so the copy is never performed.
+    /// let block = MetaBlock::decode(&mut BitReader::new(&data)).unwrap();
+    /// assert_eq!(block.header.length, 1);
+    /// assert_eq!(block.into_data(), b"z");
+    /// ```
+    ///
+    /// A truncated meta-block reports the bit offset at which the input ran out:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, MetaBlock, MetaBlockError};
+    ///
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// for len in 1..stream.len() {
+    ///     let mut reader = BitReader::new(&stream[..len]);
+    ///     reader.read_bits(1).unwrap(); // WBITS = 16
+    ///
+    ///     match MetaBlock::decode(&mut reader) {
+    ///         Err(MetaBlockError::NeedMoreInput { bit_offset }) => assert_eq!(bit_offset, len * 8),
+    ///         other => panic!("unexpected result: {:?}", other.err()),
+    ///     }
+    /// }
+    /// ```
     pub fn decode(reader: &mut BitReader) -> Result<Self, MetaBlockError> {
-        let header = MetaBlock::parse_header(reader)?;
+        MetaBlock::decode_prefix(reader, usize::MAX)
+    }
+
+    /// Decode a single meta-block from the stream, stopping after at most `max_len` bytes.
+    ///
+    /// If the limit is reached before the end of the meta-block, decoding stops there and
+    /// the reader is left in the middle of the meta-block.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `max_len` - The maximum number of bytes to decode.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `MetaBlock::decode`.
+    pub fn decode_prefix(reader: &mut BitReader, max_len: usize) -> Result<Self, MetaBlockError> {
+        let mut context = DecodeContext {
+            max_len,
+            ..DecodeContext::new()
+        };
+
+        MetaBlock::decode_with_context(reader, &mut context)
+    }
+
+    /// Decode a single meta-block that continues a stream.
+    ///
+    /// Backward references may reach into the context's window, and the context's distance
+    /// ring is used and updated. On success, the meta-block's output is appended to the
+    /// window, so the same context can be passed for the next meta-block.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `context` - The state carried over from earlier meta-blocks of the stream.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `MetaBlock::decode`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, DecodeContext, MetaBlock};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 13).unwrap(); // single block type and tree, NPOSTFIX = NDIRECT = 0
+    /// writer.write_simple_code(&[b'!' as u16], 256).unwrap(); // literals
+    /// writer.write_simple_code(&[130], 704).unwrap(); // insert 0, copy 4
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance, initially 4
+    /// let data = writer.into_inner();
+    ///
+    /// let mut context = DecodeContext::with_history(b"hello");
+    /// let block = MetaBlock::decode_with_context(&mut BitReader::new(&data), &mut context).unwrap();
+    ///
+    /// assert_eq!(block.into_data(), b"ello");
+    /// assert_eq!(context.window, b"helloello");
+    /// ```
+    ///
+    /// Commands with an implicit distance reuse the last distance without reading a
+    /// distance symbol, and leave the distance ring unchanged:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, DecodeContext, MetaBlock};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(8, 16).unwrap(); // MLEN = 9
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'x' as u16, b'y' as u16], 256).unwrap();
+    /// // Insert 2, copy 2 with the implicit distance (code 0); insert 3, copy 2.
+    /// writer.write_simple_code(&[16, 152], 704).unwrap();
+    /// writer.write_simple_code(&[4], 64).unwrap(); // last distance - 1
+    /// writer.write_bits(0b0_01, 3).unwrap(); // implicit command, literals "xy"
+    /// writer.write_bits(0b1_010, 4).unwrap(); // explicit command, literals "xyx"
+    /// let data = writer.into_inner();
+    ///
+    /// let mut context = DecodeContext::with_history(b"abcd");
+    /// let block = MetaBlock::decode_with_context(&mut BitReader::new(&data), &mut context).unwrap();
+    ///
+    /// // "xy" then "cd" at the initial last distance of 4; "xyx" then "xy" at distance 3.
+    /// assert_eq!(block.into_data(), b"xycdxyxxy");
+    ///
+    /// // Only the explicit distance was pushed onto the initial ring of [16, 15, 11, 4].
+    /// assert_eq!(context.ring.last(0), 3);
+    /// assert_eq!(context.ring.last(1), 4);
+    /// assert_eq!(context.ring.last(2), 11);
+    /// ```
+    ///
+    /// A distance up to both the window size and the bytes available reads from the
+    /// window, while one past either references the static dictionary:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, DecodeContext, MetaBlock, MetaBlockError};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'!' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[130], 704).unwrap(); // insert 0, copy 4
+    /// writer.write_simple_code(&[0], 64).unwrap(); // last distance
+    /// let data = writer.into_inner();
+    ///
+    /// let decode = |history: &[u8], window_size, distance| {
+    ///     let mut context = DecodeContext::with_history(history);
+    ///     context.window_size = window_size;
+    ///     context.ring.push(distance);
+    ///     MetaBlock::decode_with_context(&mut BitReader::new(&data), &mut context)
+    /// };
+    ///
+    /// // The window size is the limit.
+    /// assert_eq!(decode(b"0123456789", 6, 6).unwrap().into_data(), b"4567");
+    /// assert!(matches!(decode(b"0123456789", 6, 7), Err(MetaBlockError::Unsupported)));
+    ///
+    /// // The start of the window is the limit.
+    /// assert_eq!(decode(b"012345", 8, 6).unwrap().into_data(), b"0123");
+    /// assert!(matches!(decode(b"012345", 8, 7), Err(MetaBlockError::Unsupported)));
+    /// ```
+    pub fn decode_with_context(
+        reader: &mut BitReader,
+        context: &mut DecodeContext,
+    ) -> Result<Self, MetaBlockError> {
+        let mut block = MetaBlock::begin(reader, context)?;
+        while block.step(reader, context)? != DecodeState::Done {}
+
+        Ok(block)
+    }
+
+    /// Decode a single meta-block that continues a stream and return its commands.
+    ///
+    /// The commands are returned with their lengths and distances resolved, in stream
+    /// order. The context is updated exactly as by `MetaBlock::decode_with_context`, so
+    /// consecutive meta-blocks can be analyzed with the same context.
+    ///
+    /// A command whose literals complete the meta-block has its copy ignored. It is
+    /// returned with a copy length of 0 and the last distance as its distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `context` - The state carried over from earlier meta-blocks of the stream.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the commands of the meta-block.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `MetaBlock::decode`. References to the static
+    ///   dictionary are not supported yet and return `MetaBlockError::Unsupported`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::command::{Command, DistanceSource};
+    /// use brotschneider::{BitReader, BitWriter, DecodeContext, MetaBlock};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(11, 16).unwrap(); // MLEN = 12
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'x' as u16, b'y' as u16], 256).unwrap();
+    /// // Insert 2, copy 2 with the implicit distance; insert 3, copy 2.
+    /// writer.write_simple_code(&[16, 152], 704).unwrap();
+    /// writer.write_simple_code(&[4], 64).unwrap(); // last distance - 1
+    /// writer.write_bits(0b0_01, 3).unwrap(); // implicit command, literals "xy"
+    /// writer.write_bits(0b1_010, 4).unwrap(); // explicit command, literals "xyx"
+    /// writer.write_bits(0b1_010, 4).unwrap(); // explicit command, literals "xyx"
+    /// let data = writer.into_inner();
+    ///
+    /// let mut context = DecodeContext::with_history(b"abcd");
+    /// let commands = MetaBlock::decode_commands(&mut BitReader::new(&data), &mut context).unwrap();
+    ///
+    /// let command = |insert_len, copy_len, distance| Command {
+    ///     insert_len,
+    ///     copy_len,
+    ///     distance,
+    ///     source: DistanceSource::Ring(0),
+    /// };
+    /// assert_eq!(
+    ///     commands,
+    ///     [command(2, 2, 4), command(3, 2, 3), command(3, 0, 3)]
+    /// );
+    /// // The literals of the last command complete the meta-block.
+    /// assert_eq!(context.window, b"abcdxycdxyxxyxyx");
+    /// ```
+    pub fn decode_commands(
+        reader: &mut BitReader,
+        context: &mut DecodeContext,
+    ) -> Result<Vec<Command>, MetaBlockError> {
+        let mut block = MetaBlock::begin(reader, context)?;
+        block.commands = Some(Vec::new());
+        while block.step(reader, context)? != DecodeState::Done {}
+
+        Ok(block.commands.unwrap_or_default())
+    }
+
+    /// Decode a single meta-block that continues a stream into `out`, from `start` on.
+    ///
+    /// `out[..start]` holds the output of the earlier meta-blocks and `context.window` the
+    /// bytes before it, such as a custom dictionary, and backward references read from them
+    /// in place. The context's distance ring is updated as usual, but its window is not.
+    ///
+    /// Returns the meta-block, whose `data` stays empty, and the number of bytes written.
+    /// Writing stops at `context.max_len` bytes, like for `MetaBlock::decode_with_context`,
+    /// so it must leave room for them in `out`.
+    pub(crate) fn decode_into(
+        reader: &mut BitReader,
+        context: &mut DecodeContext,
+        out: &mut [u8],
+        start: usize,
+    ) -> Result<(Self, usize), MetaBlockError> {
+        let mut block = MetaBlock::begin_with_output(reader, context, false)?;
+
+        // The CRC is taken out of the context, which the command loop borrows.
+        let mut crc = context.crc.take();
+        let mut output = SliceOutput {
+            history: &context.window,
+            out,
+            start,
+            pos: start,
+            crc: crc.as_mut(),
+        };
+        let mut result = Ok(());
+        while block.state != DecodeState::Done {
+            match block.next_state(reader, context, &mut output) {
+                Ok(state) => block.state = state,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        let written = output.pos - start;
+        context.crc = crc;
+        result?;
+
+        context.ring = block.distance_ring.clone();
+
+        Ok((block, written))
+    }
+
+    /// Read the header and prefix codes of a meta-block, without decoding any commands.
+    ///
+    /// The returned meta-block is in the `DecodeState::ReadCommand` state, or in
+    /// `DecodeState::Done` if it carries no commands, such as a metadata meta-block. Call
+    /// `MetaBlock::step` with the same reader position and context to decode it.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `context` - The state carried over from earlier meta-blocks of the stream.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `MetaBlock::decode`.
+    pub fn begin(reader: &mut BitReader, context: &DecodeContext) -> Result<Self, MetaBlockError> {
+        Self::begin_with_output(reader, context, true)
+    }
+
+    /// Like `begin`, reserving room for the output in `data` only if `reserve_data` is set.
+    fn begin_with_output(
+        reader: &mut BitReader,
+        context: &DecodeContext,
+        reserve_data: bool,
+    ) -> Result<Self, MetaBlockError> {
+        let header = MetaBlockHeader::read(reader)?;
+
+        if header.is_last_empty || header.is_metadata {
+            if header.is_metadata {
+                reader.align_to_byte();
+                reader
+                    .skip_bits(header.length as usize * 8)
+                    .map_err(|e| with_offset(reader, e))?;
+            }
+
+            return Ok(MetaBlock {
+                header,
+                literal_trees: Vec::new(),
+                insert_copy_trees: Vec::new(),
+                distance_trees: Vec::new(),
+                literal_context_map: Vec::new(),
+                distance_context_map: Vec::new(),
+                context_modes: Vec::new(),
+                distance_params: DistanceParams::default(),
+                distance_ring: context.ring.clone(),
+                data: Vec::new(),
+                state: DecodeState::Done,
+                identity_literals: false,
+                skipped: 0,
+                commands: None,
+            });
+        }
 
         if header.is_uncompressed {
-            todo!("Uncompressed meta-blocks not yet supported");
+            // The bytes are copied in a single step, so no prefix codes are read.
+            let limit = to_index(header.length as u64)?.min(context.max_len);
+
+            return Ok(MetaBlock {
+                header,
+                literal_trees: Vec::new(),
+                insert_copy_trees: Vec::new(),
+                distance_trees: Vec::new(),
+                literal_context_map: Vec::new(),
+                distance_context_map: Vec::new(),
+                context_modes: Vec::new(),
+                distance_params: DistanceParams::default(),
+                distance_ring: context.ring.clone(),
+                data: Vec::with_capacity(if reserve_data { limit } else { 0 }),
+                state: DecodeState::ReadCommand,
+                identity_literals: false,
+                skipped: 0,
+                commands: None,
+            });
+        }
+
+        // The prefix codes for literals, insert-and-copy lengths and distances are counted as
+        // they are declared, so that a stream cannot make the decoder build more of them
+        // than allowed. Each insert-and-copy block type has its own tree.
+        let mut trees = 0;
+        let mut declare = |count: u16| {
+            trees += count as usize;
+            if trees > context.max_trees_per_block {
+                return Err(MetaBlockError::TooManyTrees {
+                    count: trees,
+                    limit: context.max_trees_per_block,
+                });
+            }
+
+            Ok(())
+        };
+
+        // Block switching is not supported yet, so each category has a single block type.
+        for category in 0..3 {
+            let nbltypes = read_nbltypes(reader)?;
+            if category == 1 {
+                declare(nbltypes)?;
+            }
+
+            if nbltypes > 1 {
+                return Err(MetaBlockError::Unsupported);
+            }
         }
 
-        // For now, assume a fixed Huffman tree or use a stub tree
-        let literal_huffman = Some(HuffmanTree::from_code_lengths(&[2, 2, 2, 2])?);
+        let distance_params = DistanceParams::read(reader).map_err(|e| with_offset(reader, e))?;
+        let context_modes = read_context_modes(reader, 1).map_err(|e| with_offset(reader, e))?;
 
-        let mut data = Vec::new();
-        for _ in 0..header.length {
-            let symbol = literal_huffman.as_ref().unwrap().decode_symbol(reader)?;
-            data.push(symbol as u8);
+        // Context maps are not supported yet, so there is a single literal and distance tree.
+        for _ in 0..2 {
+            let ntrees = read_nbltypes(reader)?;
+            declare(ntrees)?;
+
+            if ntrees > 1 {
+                return Err(MetaBlockError::Unsupported);
+            }
         }
 
+        let literal_huffman = HuffmanTree::read(reader, LITERAL_ALPHABET_SIZE)
+            .map_err(|e| huffman_with_offset(reader, e))?;
+        let insert_copy_huffman = HuffmanTree::read(reader, INSERT_COPY_ALPHABET_SIZE)
+            .map_err(|e| huffman_with_offset(reader, e))?;
+        let distance_huffman = HuffmanTree::read(reader, distance_params.alphabet_size())
+            .map_err(|e| huffman_with_offset(reader, e))?;
+
+        let limit = to_index(header.length as u64)?.min(context.max_len);
+        let identity_literals = context.fast_literals && literal_huffman.is_identity();
+
         Ok(MetaBlock {
             header,
-            literal_huffman,
-            data,
+            literal_trees: vec![literal_huffman],
+            insert_copy_trees: vec![insert_copy_huffman],
+            distance_trees: vec![distance_huffman],
+            literal_context_map: vec![0; LITERAL_CONTEXTS],
+            distance_context_map: vec![0; DISTANCE_CONTEXTS],
+            context_modes,
+            distance_params,
+            distance_ring: context.ring.clone(),
+            data: Vec::with_capacity(if reserve_data { limit } else { 0 }),
+            state: DecodeState::ReadCommand,
+            identity_literals,
+            skipped: 0,
+            commands: None,
         })
     }
 
-    /// Parse the header of a meta-block (is_last, length, is_uncompressed).
-    fn parse_header(reader: &mut BitReader) -> Result<MetaBlockHeader, MetaBlockError> {
-        let is_last = reader.read_bits(1)? != 0;
-        let length_nbits = reader.read_bits(2)? + 4;
-        let length = reader.read_bits(length_nbits as u8)?;
-        let is_uncompressed = reader.read_bits(1)? != 0;
+    /// Returns where the command loop of the meta-block stands.
+    #[inline]
+    pub fn state(&self) -> DecodeState {
+        self.state
+    }
+
+    /// Advance the command loop by one step and return the new state.
+    ///
+    /// Each step reads at most one command, one literal (or one byte-aligned run of
+    /// literals in fast literal mode) or one distance, or performs one copy. All progress is
+    /// kept in the meta-block, so decoding can stop after any step and resume later from
+    /// the same bit position, even with a new reader over the same data. On reaching
+    /// `DecodeState::Done`, the meta-block's output is appended to the context's window and
+    /// its distance ring is stored in the context; further steps do nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A mutable reference to a `BitReader` instance.
+    /// * `context` - The context the meta-block was begun with.
+    ///
+    /// # Returns
+    ///
+    /// * A Result containing the state after the step.
+    ///
+    /// # Errors
+    ///
+    /// * Returns the same errors as `MetaBlock::decode`. After an error, the meta-block
+    ///   must not be stepped again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, DecodeContext, DecodeState, MetaBlock};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'a' as u16, b'b' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[144], 704).unwrap(); // insert 2, copy 2
+    /// writer.write_simple_code(&[6], 64).unwrap(); // last distance - 2 = 2
+    /// writer.write_bits(0b01, 2).unwrap(); // literals "ab"
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// let mut context = DecodeContext::new();
+    /// let mut block = MetaBlock::begin(&mut reader, &context).unwrap();
+    /// assert_eq!(block.state(), DecodeState::ReadCommand);
+    ///
+    /// let mut states = Vec::new();
+    /// while block.state() != DecodeState::Done {
+    ///     states.push(block.step(&mut reader, &mut context).unwrap());
+    /// }
+    ///
+    /// let insert = |remaining| DecodeState::InsertLiterals {
+    ///     remaining,
+    ///     copy_len: 2,
+    ///     implicit_distance: false,
+    /// };
+    /// assert_eq!(
+    ///     states,
+    ///     [
+    ///         insert(2),
+    ///         insert(1),
+    ///         insert(0),
+    ///         DecodeState::ReadDistance { copy_len: 2 },
+    ///         DecodeState::Copy { remaining: 2, distance: 2 },
+    ///         DecodeState::ReadCommand,
+    ///         DecodeState::Done,
+    ///     ]
+    /// );
+    /// assert_eq!(context.window, b"abab");
+    /// ```
+    ///
+    /// Insert-and-copy symbols are checked against the 704-symbol alphabet, even if the
+    /// tree was built for a larger one:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, DecodeContext, HuffmanTree, MetaBlock};
+    /// use brotschneider::MetaBlockError;
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(3, 16).unwrap(); // MLEN = 4
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'a' as u16], 256).unwrap();
+    /// writer.write_simple_code(&[130], 704).unwrap();
+    /// writer.write_simple_code(&[0], 64).unwrap();
+    /// let data = writer.into_inner();
+    ///
+    /// let mut reader = BitReader::new(&data);
+    /// let mut context = DecodeContext::new();
+    /// let mut block = MetaBlock::begin(&mut reader, &context).unwrap();
+    ///
+    /// // A tree whose only symbol is 704.
+    /// let mut lengths = vec![0u8; 705];
+    /// lengths[704] = 1;
+    /// block.insert_copy_trees[0] = HuffmanTree::from_code_lengths(&lengths).unwrap();
+    ///
+    /// assert!(matches!(
+    ///     block.step(&mut reader, &mut context),
+    ///     Err(MetaBlockError::Corrupt {
+    ///         reason: "insert-and-copy symbol out of range"
+    ///     })
+    /// ));
+    /// ```
+    ///
+    /// Decoding can be suspended in any state and resumed with a fresh reader:
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, BitWriter, DecodeContext, DecodeState, MetaBlock};
+    ///
+    /// let mut writer = BitWriter::new();
+    /// writer.write_bits(0b1000, 4).unwrap(); // ISLAST, ISLASTEMPTY = 0, MNIBBLES = 4
+    /// writer.write_bits(8, 16).unwrap(); // MLEN = 9
+    /// writer.write_bits(0, 13).unwrap();
+    /// writer.write_simple_code(&[b'x' as u16, b'y' as u16], 256).unwrap();
+    /// // Insert 2, copy 2 with the implicit distance; insert 3, copy 2.
+    /// writer.write_simple_code(&[16, 152], 704).unwrap();
+    /// writer.write_simple_code(&[4], 64).unwrap(); // last distance - 1
+    /// writer.write_bits(0b0_01, 3).unwrap(); // implicit command, literals "xy"
+    /// writer.write_bits(0b1_010, 4).unwrap(); // explicit command, literals "xyx"
+    /// let data = writer.into_inner();
+    ///
+    /// let mut suspended_in = Vec::new();
+    /// for steps in 0.. {
+    ///     let mut context = DecodeContext::with_history(b"abcd");
+    ///     let mut reader = BitReader::new(&data);
+    ///     let mut block = MetaBlock::begin(&mut reader, &context).unwrap();
+    ///     for _ in 0..steps {
+    ///         block.step(&mut reader, &mut context).unwrap();
+    ///     }
+    ///     if block.state() == DecodeState::Done {
+    ///         break;
+    ///     }
+    ///     suspended_in.push(block.state());
+    ///
+    ///     // Nothing but the bit position carries over to the new reader.
+    ///     let offset = reader.bit_offset();
+    ///     let mut reader = BitReader::new_at(&data, offset / 8, (offset % 8) as u8).unwrap();
+    ///     while block.step(&mut reader, &mut context).unwrap() != DecodeState::Done {}
+    ///
+    ///     assert_eq!(block.into_data(), b"xycdxyxxy");
+    ///     assert_eq!(context.window, b"abcdxycdxyxxy");
+    ///     assert_eq!(context.ring.last(0), 3);
+    /// }
+    ///
+    /// // Every kind of state was suspended in, including a copy with an implicit distance.
+    /// assert!(suspended_in.contains(&DecodeState::Copy { remaining: 2, distance: 4 }));
+    /// assert!(suspended_in.contains(&DecodeState::ReadDistance { copy_len: 2 }));
+    /// assert!(suspended_in.iter().any(|s| matches!(s, DecodeState::InsertLiterals { .. })));
+    /// assert_eq!(suspended_in[0], DecodeState::ReadCommand);
+    /// ```
+    pub fn step(
+        &mut self,
+        reader: &mut BitReader,
+        context: &mut DecodeContext,
+    ) -> Result<DecodeState, MetaBlockError> {
+        if self.state == DecodeState::Done {
+            return Ok(DecodeState::Done);
+        }
+
+        // The data and CRC are taken out while the command loop borrows the context.
+        let mut data = std::mem::take(&mut self.data);
+        let mut crc = context.crc.take();
+        let next = self.next_state(
+            reader,
+            context,
+            &mut WindowOutput {
+                history: &context.window,
+                data: &mut data,
+                crc: crc.as_mut(),
+            },
+        );
+        self.data = data;
+        context.crc = crc;
+        let next = next?;
+        self.state = next;
+
+        if next == DecodeState::Done {
+            context.ring = self.distance_ring.clone();
+            context.window.extend_from_slice(&self.data);
+        }
+
+        Ok(next)
+    }
+
+    /// Copies the bytes of an uncompressed meta-block, which start at the next byte
+    /// boundary, and finishes the meta-block.
+    fn read_uncompressed(
+        &mut self,
+        reader: &mut BitReader,
+        max_len: usize,
+        output: &mut impl MetaBlockOutput,
+    ) -> Result<DecodeState, MetaBlockError> {
+        let limit = to_index(self.header.length as u64)?.min(max_len);
 
-        Ok(MetaBlockHeader {
-            is_last,
-            length,
-            is_uncompressed,
+        reader.align_to_byte();
+        let bytes = reader.remaining_slice();
+        reader
+            .skip_bits(limit * 8)
+            .map_err(|e| with_offset(reader, e))?;
+        output.extend_from_slice(&bytes[..limit])?;
+
+        Ok(DecodeState::Done)
+    }
+
+    /// Perform one step of the command loop from the current state.
+    fn next_state(
+        &mut self,
+        reader: &mut BitReader,
+        context: &DecodeContext,
+        output: &mut impl MetaBlockOutput,
+    ) -> Result<DecodeState, MetaBlockError> {
+        if self.header.is_uncompressed {
+            return self.read_uncompressed(reader, context.max_len, output);
+        }
+
+        // With a single block type and tree per category, every context uses the first tree.
+        let (Some(literal_huffman), Some(insert_copy_huffman), Some(distance_huffman)) = (
+            self.literal_trees.first(),
+            self.insert_copy_trees.first(),
+            self.distance_trees.first(),
+        ) else {
+            return Ok(DecodeState::Done);
+        };
+
+        // Lengths are tracked as u64 so that the bound checks cannot wrap on targets where
+        // usize is narrower; they are converted back only to index the output.
+        let length = self.header.length as u64;
+        let limit = to_index(length)?.min(context.max_len);
+        // The number of bytes the meta-block has produced, including skipped copies.
+        let produced = output.written() + self.skipped;
+
+        // Once the limit is reached, the rest of the current command is ignored.
+        if produced >= limit {
+            return Ok(DecodeState::Done);
+        }
+
+        match self.state {
+            DecodeState::ReadCommand => {
+                let symbol = insert_copy_huffman
+                    .decode_symbol(reader)
+                    .map_err(|e| huffman_with_offset(reader, e))?;
+                // A tree built for a larger alphabet would index past the length tables.
+                if symbol as usize >= INSERT_COPY_ALPHABET_SIZE {
+                    return Err(MetaBlockError::Corrupt {
+                        reason: "insert-and-copy symbol out of range",
+                    });
+                }
+                let lengths =
+                    InsertCopy::read(symbol, reader).map_err(|e| with_offset(reader, e))?;
+
+                if produced as u64 + lengths.insert_len as u64 > length {
+                    return Err(MetaBlockError::Corrupt {
+                        reason: "insert length exceeds meta-block length",
+                    });
+                }
+
+                if let Some(commands) = &mut self.commands {
+                    // The copy is filled in once its distance is known.
+                    commands.push(Command {
+                        insert_len: lengths.insert_len,
+                        copy_len: 0,
+                        distance: self.distance_ring.last(0),
+                        source: DistanceSource::Ring(0),
+                    });
+                }
+
+                Ok(DecodeState::InsertLiterals {
+                    remaining: lengths.insert_len,
+                    copy_len: lengths.copy_len,
+                    implicit_distance: lengths.implicit_distance,
+                })
+            }
+            DecodeState::InsertLiterals {
+                remaining: 0,
+                copy_len,
+                implicit_distance: true,
+            } => self.start_copy(self.distance_ring.last(0), 0, copy_len, context, output),
+            DecodeState::InsertLiterals {
+                remaining: 0,
+                copy_len,
+                implicit_distance: false,
+            } => Ok(DecodeState::ReadDistance { copy_len }),
+            DecodeState::InsertLiterals {
+                remaining,
+                copy_len,
+                implicit_distance,
+            } => {
+                let mut inserted = 0;
+                if self.identity_literals && reader.bit_offset().is_multiple_of(8) {
+                    // Each literal is its own byte, so the run can be copied directly.
+                    let n = (remaining as usize).min(limit - produced);
+                    if let Some(literals) = reader.remaining_slice().get(..n) {
+                        output.extend_from_slice(literals)?;
+                        reader
+                            .skip_bits(n * 8)
+                            .map_err(|e| with_offset(reader, e))?;
+                        inserted = n as u32;
+                    }
+                }
+
+                if inserted == 0 {
+                    output.push(literal_byte(
+                        literal_huffman
+                            .decode_symbol(reader)
+                            .map_err(|e| huffman_with_offset(reader, e))?,
+                    )?)?;
+                    inserted = 1;
+                }
+
+                Ok(DecodeState::InsertLiterals {
+                    remaining: remaining - inserted,
+                    copy_len,
+                    implicit_distance,
+                })
+            }
+            DecodeState::ReadDistance { copy_len } => {
+                let code = distance_huffman
+                    .decode_symbol(reader)
+                    .map_err(|e| huffman_with_offset(reader, e))? as u32;
+                let distance = if code < NUM_DISTANCE_SHORT_CODES {
+                    self.distance_ring
+                        .resolve_short_code(code)
+                        .ok_or(MetaBlockError::Corrupt {
+                            reason: "invalid distance",
+                        })?
+                } else {
+                    self.distance_params
+                        .decode(code, reader)
+                        .map_err(|e| with_offset(reader, e))?
+                };
+
+                self.start_copy(distance, code, copy_len, context, output)
+            }
+            DecodeState::Copy {
+                remaining,
+                distance,
+            } => {
+                let n = (remaining as usize).min(limit - produced);
+                if context.literals_only {
+                    self.skipped += n;
+                    return Ok(DecodeState::ReadCommand);
+                }
+
+                output.copy(distance, n)?;
+
+                Ok(DecodeState::ReadCommand)
+            }
+            DecodeState::Done => Ok(DecodeState::Done),
+        }
+    }
+
+    /// Check a resolved distance and copy length, and enter the copy state.
+    fn start_copy(
+        &mut self,
+        distance: u32,
+        code: u32,
+        copy_len: u32,
+        context: &DecodeContext,
+        output: &impl MetaBlockOutput,
+    ) -> Result<DecodeState, MetaBlockError> {
+        let produced = output.written() + self.skipped;
+
+        // A distance up to the window size, and up to the start of the window while it
+        // fills, reads from the output; any larger distance references the static
+        // dictionary, which is not supported yet. Without copies, the window is incomplete,
+        // so the check is meaningless in literals-only mode.
+        let max_distance =
+            (context.window_size as u64).min(output.history_len() as u64 + produced as u64);
+        if !context.literals_only && distance as u64 > max_distance {
+            return Err(MetaBlockError::Unsupported);
+        }
+
+        if code != 0 {
+            self.distance_ring.push(distance);
+        }
+
+        if let Some(command) = self
+            .commands
+            .as_mut()
+            .and_then(|commands| commands.last_mut())
+        {
+            command.copy_len = copy_len;
+            command.distance = distance;
+            command.source = match SHORT_CODE_INDEX.get(code as usize) {
+                Some(&index) => DistanceSource::Ring(index),
+                None => DistanceSource::Explicit,
+            };
+        }
+
+        if produced as u64 + copy_len as u64 > self.header.length as u64 {
+            return Err(MetaBlockError::Corrupt {
+                reason: "copy length exceeds meta-block length",
+            });
+        }
+
+        Ok(DecodeState::Copy {
+            remaining: copy_len,
+            distance,
         })
     }
+
+    /// Consumes the MetaBlock and returns its decoded data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, MetaBlock};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    /// let mut reader = BitReader::new(&stream);
+    /// reader.read_bits(1).unwrap(); // WBITS = 16
+    ///
+    /// let block = MetaBlock::decode(&mut reader).unwrap();
+    /// assert_eq!(block.into_data(), b"abab");
+    /// ```
+    #[inline]
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns the first prefix code of each category used to decode the meta-block.
+    ///
+    /// The codes are `None` for meta-blocks that carry none, such as metadata and empty
+    /// last meta-blocks. Use `literal_trees`, `insert_copy_trees` and `distance_trees` to
+    /// see every code of a category.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, MetaBlock};
+    ///
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    /// let mut reader = BitReader::new(&stream);
+    /// reader.read_bits(1).unwrap(); // WBITS = 16
+    ///
+    /// let block = MetaBlock::decode(&mut reader).unwrap();
+    /// let trees = block.trees();
+    /// assert_eq!(trees.literal.unwrap().max_bits, 1);
+    /// assert_eq!(trees.insert_copy.unwrap().max_bits, 0);
+    /// assert_eq!(trees.distance.unwrap().max_bits, 0);
+    ///
+    /// // An empty last meta-block.
+    /// let mut reader = BitReader::new(&[0b1100_0000]);
+    /// let block = MetaBlock::decode(&mut reader).unwrap();
+    /// assert!(block.trees().literal.is_none());
+    /// ```
+    #[inline]
+    pub fn trees(&self) -> MetaBlockTrees<'_> {
+        MetaBlockTrees {
+            literal: self.literal_trees.first(),
+            insert_copy: self.insert_copy_trees.first(),
+            distance: self.distance_trees.first(),
+        }
+    }
+
+    /// Returns the prefix codes for literals, in the order the context map refers to them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, MetaBlock};
+    ///
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    /// let mut reader = BitReader::new(&stream);
+    /// reader.read_bits(1).unwrap(); // WBITS = 16
+    ///
+    /// let block = MetaBlock::decode(&mut reader).unwrap();
+    /// assert_eq!(block.literal_trees().len(), 1);
+    /// assert_eq!(block.insert_copy_trees().len(), 1);
+    /// assert_eq!(block.distance_trees().len(), 1);
+    ///
+    /// // Every context of the single block type maps to the single tree.
+    /// assert_eq!(block.literal_context_map(), [0; 64]);
+    /// assert_eq!(block.distance_context_map(), [0; 4]);
+    /// ```
+    #[inline]
+    pub fn literal_trees(&self) -> &[HuffmanTree] {
+        &self.literal_trees
+    }
+
+    /// Returns the prefix codes for insert-and-copy length symbols, one per block type.
+    #[inline]
+    pub fn insert_copy_trees(&self) -> &[HuffmanTree] {
+        &self.insert_copy_trees
+    }
+
+    /// Returns the prefix codes for distance symbols, in the order the context map refers
+    /// to them.
+    #[inline]
+    pub fn distance_trees(&self) -> &[HuffmanTree] {
+        &self.distance_trees
+    }
+
+    /// Returns the literal context map: the index into `literal_trees` for each literal
+    /// block type and context, with 64 contexts per block type.
+    #[inline]
+    pub fn literal_context_map(&self) -> &[u8] {
+        &self.literal_context_map
+    }
+
+    /// Returns the distance context map: the index into `distance_trees` for each distance
+    /// block type and context, with 4 contexts per block type.
+    #[inline]
+    pub fn distance_context_map(&self) -> &[u8] {
+        &self.distance_context_map
+    }
+
+    /// Returns the distance ring buffer as it stood at the end of the meta-block.
+    ///
+    /// This shows which recent distances the short distance codes could refer to when the
+    /// meta-block ended.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::{BitReader, MetaBlock};
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    /// let mut reader = BitReader::new(&stream);
+    /// reader.read_bits(1).unwrap(); // WBITS = 16
+    ///
+    /// let block = MetaBlock::decode(&mut reader).unwrap();
+    /// assert_eq!(block.distance_ring().last(0), 2);
+    /// assert_eq!(block.distance_ring().last(1), 4);
+    /// ```
+    #[inline]
+    pub fn distance_ring(&self) -> &DistanceRing {
+        &self.distance_ring
+    }
+
+    /// Look up the context mode for a literal block type.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_type` - The literal block type.
+    ///
+    /// # Returns
+    ///
+    /// * The context mode for the block type, or `None` if the block type is out of range.
+    #[inline]
+    pub fn context_mode(&self, block_type: usize) -> Option<ContextMode> {
+        self.context_modes.get(block_type).copied()
+    }
+}
+
+/// Where the command loop writes the output of a meta-block.
+///
+/// Backward references reach through the output of the meta-block into the bytes that
+/// precede it.
+trait MetaBlockOutput {
+    /// Returns the number of bytes before the output of the meta-block.
+    fn history_len(&self) -> usize;
+
+    /// Returns the number of bytes the meta-block has written.
+    fn written(&self) -> usize;
+
+    /// Append a byte to the output.
+    fn push(&mut self, byte: u8) -> Result<(), MetaBlockError>;
+
+    /// Append bytes to the output.
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), MetaBlockError>;
+
+    /// Append `n` bytes copied from `distance` bytes back. The copy may overlap the bytes
+    /// it appends.
+    fn copy(&mut self, distance: u32, n: usize) -> Result<(), MetaBlockError>;
+}
+
+/// Output into the `data` of a meta-block, after the window of the stream.
+struct WindowOutput<'a> {
+    /// The window of the stream, before the meta-block.
+    history: &'a [u8],
+    /// The output of the meta-block.
+    data: &'a mut Vec<u8>,
+    /// The CRC to add the written bytes to, if one is being computed.
+    crc: Option<&'a mut Crc32>,
+}
+
+impl MetaBlockOutput for WindowOutput<'_> {
+    #[inline]
+    fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    #[inline]
+    fn written(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    fn push(&mut self, byte: u8) -> Result<(), MetaBlockError> {
+        self.data.push(byte);
+        if let Some(crc) = &mut self.crc {
+            crc.update(&[byte]);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), MetaBlockError> {
+        self.data.extend_from_slice(bytes);
+        if let Some(crc) = &mut self.crc {
+            crc.update(bytes);
+        }
+        Ok(())
+    }
+
+    fn copy(&mut self, distance: u32, n: usize) -> Result<(), MetaBlockError> {
+        let history = self.history;
+        let start = self.data.len();
+        for _ in 0..n {
+            let pos = to_index(history.len() as u64 + self.data.len() as u64 - distance as u64)?;
+            let byte = match history.get(pos) {
+                Some(&byte) => byte,
+                None => get_or_corrupt(self.data, pos - history.len())?,
+            };
+            self.data.push(byte);
+        }
+        if let Some(crc) = &mut self.crc {
+            crc.update(&self.data[start..]);
+        }
+
+        Ok(())
+    }
+}
+
+/// Output into a caller-provided buffer that also holds the earlier output of the stream.
+struct SliceOutput<'a> {
+    /// The bytes before the output of the stream, such as a custom dictionary.
+    history: &'a [u8],
+    /// The output of the stream.
+    out: &'a mut [u8],
+    /// The position in `out` where the output of the meta-block starts.
+    start: usize,
+    /// The position in `out` of the next byte to write.
+    pos: usize,
+    /// The CRC to add the written bytes to, if one is being computed.
+    crc: Option<&'a mut Crc32>,
+}
+
+impl MetaBlockOutput for SliceOutput<'_> {
+    #[inline]
+    fn history_len(&self) -> usize {
+        self.history.len() + self.start
+    }
+
+    #[inline]
+    fn written(&self) -> usize {
+        self.pos - self.start
+    }
+
+    #[inline]
+    fn push(&mut self, byte: u8) -> Result<(), MetaBlockError> {
+        *self
+            .out
+            .get_mut(self.pos)
+            .ok_or(MetaBlockError::OutputTooLarge)? = byte;
+        self.pos += 1;
+        if let Some(crc) = &mut self.crc {
+            crc.update(&[byte]);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), MetaBlockError> {
+        self.out
+            .get_mut(self.pos..self.pos + bytes.len())
+            .ok_or(MetaBlockError::OutputTooLarge)?
+            .copy_from_slice(bytes);
+        self.pos += bytes.len();
+        if let Some(crc) = &mut self.crc {
+            crc.update(bytes);
+        }
+        Ok(())
+    }
+
+    fn copy(&mut self, distance: u32, n: usize) -> Result<(), MetaBlockError> {
+        if self.out.len() - self.pos < n {
+            return Err(MetaBlockError::OutputTooLarge);
+        }
+
+        let history = self.history;
+        let start = self.pos;
+        for _ in 0..n {
+            let pos = to_index(history.len() as u64 + self.pos as u64 - distance as u64)?;
+            let byte = match pos.checked_sub(history.len()) {
+                Some(pos) if pos < self.pos => self.out[pos],
+                Some(_) => {
+                    return Err(MetaBlockError::Corrupt {
+                        reason: "index out of range",
+                    });
+                }
+                None => get_or_corrupt(history, pos)?,
+            };
+            self.out[self.pos] = byte;
+            self.pos += 1;
+        }
+        if let Some(crc) = &mut self.crc {
+            crc.update(&self.out[start..self.pos]);
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert a decoded literal symbol to the byte it encodes.
+///
+/// Literal symbols must be in 0..=255; a larger symbol means the wrong tree was used, so it is
+/// rejected rather than truncated.
+#[inline]
+fn literal_byte(symbol: u16) -> Result<u8, MetaBlockError> {
+    u8::try_from(symbol).map_err(|_| MetaBlockError::Corrupt {
+        reason: "literal symbol out of range",
+    })
+}
+
+/// Convert an output position or length to an index, or `Corrupt` if it does not fit.
+#[inline]
+fn to_index(position: u64) -> Result<usize, MetaBlockError> {
+    usize::try_from(position).map_err(|_| MetaBlockError::Corrupt {
+        reason: "output position exceeds the address space",
+    })
+}
+
+/// Convert a read error into a `MetaBlockError` that records where the reader stopped.
+///
+/// Running out of input becomes `MetaBlockError::NeedMoreInput`; other read errors are
+/// wrapped as usual.
+pub(crate) fn with_offset(reader: &BitReader, e: BitReaderError) -> MetaBlockError {
+    match e {
+        BitReaderError::UnexpectedEndOfInput => MetaBlockError::NeedMoreInput {
+            bit_offset: reader.bit_offset(),
+        },
+        e => e.into(),
+    }
+}
+
+/// Like `with_offset`, for read errors raised while decoding a prefix code.
+pub(crate) fn huffman_with_offset(reader: &BitReader, e: HuffmanError) -> MetaBlockError {
+    match e {
+        HuffmanError::BitReaderError(e) => with_offset(reader, e),
+        e => e.into(),
+    }
+}
+
+/// Returns the element at a computed index, or `Corrupt` if the index is out of range.
+///
+/// Offsets derived from the stream are checked here so that a malformed stream produces an
+/// error instead of a panic.
+#[inline]
+pub(crate) fn get_or_corrupt<T: Copy>(slice: &[T], idx: usize) -> Result<T, MetaBlockError> {
+    slice.get(idx).copied().ok_or(MetaBlockError::Corrupt {
+        reason: "index out of range",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_index_accepts_the_last_position() {
+        assert!(matches!(to_index(usize::MAX as u64), Ok(usize::MAX)));
+    }
+
+    // Only targets with a usize narrower than u64 have positions past it.
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    fn to_index_rejects_positions_past_usize() {
+        for position in [usize::MAX as u64 + 1, u64::MAX] {
+            assert!(matches!(
+                to_index(position),
+                Err(MetaBlockError::Corrupt {
+                    reason: "output position exceeds the address space"
+                })
+            ));
+        }
+    }
 }
@@ -0,0 +1,168 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::{HuffmanTree, MetaBlock, MetaBlockHeader};
+
+/// The structure of a decoded stream, meta-block by meta-block.
+///
+/// The `Display` implementation prints the outline as an indented tree, one line per
+/// meta-block followed by the layout of its prefix codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamOutline {
+    /// The base-2 logarithm of the window size declared by the stream (WBITS).
+    pub window_bits: u8,
+    /// An outline of each meta-block, in stream order.
+    pub meta_blocks: Vec<MetaBlockOutline>,
+}
+
+/// The structure of a single meta-block within a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaBlockOutline {
+    /// The offset of the meta-block header in the compressed stream, in bits.
+    pub bit_offset: usize,
+    /// The header of the meta-block.
+    pub header: MetaBlockHeader,
+    /// The range of the decompressed output produced by the meta-block.
+    pub output_range: Range<usize>,
+    /// The number of literal, insert-and-copy and distance block types, in that order.
+    ///
+    /// All zero for meta-blocks that carry no prefix codes.
+    pub block_types: [usize; 3],
+    /// A summary of each prefix code for literals.
+    pub literal_trees: Vec<TreeOutline>,
+    /// A summary of each prefix code for insert-and-copy length symbols.
+    pub insert_copy_trees: Vec<TreeOutline>,
+    /// A summary of each prefix code for distance symbols.
+    pub distance_trees: Vec<TreeOutline>,
+    /// The number of insert-and-copy commands decoded.
+    pub commands: usize,
+}
+
+/// The shape of a single prefix code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeOutline {
+    /// The number of symbols with a code.
+    pub symbols: usize,
+    /// The length of the longest code, in bits.
+    pub max_bits: u8,
+}
+
+impl MetaBlockOutline {
+    /// Outline a fully decoded meta-block.
+    pub(crate) fn new(bit_offset: usize, output_start: usize, block: &MetaBlock) -> Self {
+        let outline = |trees: &[HuffmanTree]| trees.iter().map(TreeOutline::new).collect();
+
+        Self {
+            bit_offset,
+            header: block.header.clone(),
+            output_range: output_start..output_start + block.data.len(),
+            block_types: [
+                block.context_modes.len(),
+                block.insert_copy_trees.len(),
+                block.distance_context_map.len() / 4,
+            ],
+            literal_trees: outline(&block.literal_trees),
+            insert_copy_trees: outline(&block.insert_copy_trees),
+            distance_trees: outline(&block.distance_trees),
+            commands: block.commands.as_ref().map_or(0, Vec::len),
+        }
+    }
+
+    /// Returns what kind of meta-block this is, for display.
+    fn kind(&self) -> &'static str {
+        if self.header.is_metadata {
+            "metadata"
+        } else if self.header.is_last_empty {
+            "empty"
+        } else if self.header.is_uncompressed {
+            "uncompressed"
+        } else {
+            "compressed"
+        }
+    }
+}
+
+impl TreeOutline {
+    /// Summarize a prefix code.
+    fn new(tree: &HuffmanTree) -> Self {
+        Self {
+            symbols: tree.code_lengths.iter().filter(|&&len| len != 0).count(),
+            max_bits: tree.max_bits,
+        }
+    }
+}
+
+impl fmt::Display for StreamOutline {
+    /// Formats the outline as an indented tree.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::outline;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    ///
+    /// assert_eq!(
+    ///     outline(&stream).unwrap().to_string(),
+    ///     "stream: WBITS 16, 1 meta-block\n\
+    ///      \x20 meta-block 0 at bit 1: compressed, last, length 4, output 0..4\n\
+    ///      \x20   block types: 1 literal, 1 insert-and-copy, 1 distance\n\
+    ///      \x20   literal trees: [2 symbols, 1 bits]\n\
+    ///      \x20   insert-and-copy trees: [1 symbols, 0 bits]\n\
+    ///      \x20   distance trees: [1 symbols, 0 bits]\n\
+    ///      \x20   commands: 1\n"
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.meta_blocks.len();
+        writeln!(
+            f,
+            "stream: WBITS {}, {count} meta-block{}",
+            self.window_bits,
+            if count == 1 { "" } else { "s" }
+        )?;
+
+        for (i, meta_block) in self.meta_blocks.iter().enumerate() {
+            write!(f, "  meta-block {i} at bit {}: ", meta_block.bit_offset)?;
+            write!(f, "{}", meta_block.kind())?;
+            if meta_block.header.is_last {
+                write!(f, ", last")?;
+            }
+            if meta_block.header.is_last_empty {
+                writeln!(f)?;
+                continue;
+            }
+            write!(f, ", length {}", meta_block.header.length)?;
+            let Range { start, end } = meta_block.output_range;
+            writeln!(f, ", output {start}..{end}")?;
+
+            if meta_block.literal_trees.is_empty() {
+                continue;
+            }
+
+            let [literal, insert_copy, distance] = meta_block.block_types;
+            writeln!(
+                f,
+                "    block types: {literal} literal, {insert_copy} insert-and-copy, {distance} distance"
+            )?;
+            for (name, trees) in [
+                ("literal", &meta_block.literal_trees),
+                ("insert-and-copy", &meta_block.insert_copy_trees),
+                ("distance", &meta_block.distance_trees),
+            ] {
+                write!(f, "    {name} trees: [")?;
+                for (j, tree) in trees.iter().enumerate() {
+                    if j > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{} symbols, {} bits", tree.symbols, tree.max_bits)?;
+                }
+                writeln!(f, "]")?;
+            }
+            writeln!(f, "    commands: {}", meta_block.commands)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::ops::{Deref, Range};
+
+use crate::MetaBlockError;
+
+/// Decompressed holds the output of a decoded stream along with statistics about it.
+///
+/// It dereferences to the decompressed bytes, so it can be used wherever a byte slice is
+/// expected, and converts into a `Vec<u8>` without copying.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Decompressed {
+    /// The decompressed bytes.
+    data: Vec<u8>,
+    /// The number of meta-blocks decoded, including metadata and empty ones.
+    meta_blocks: usize,
+}
+
+impl Decompressed {
+    /// Creates a new Decompressed from decoded bytes and the number of meta-blocks they
+    /// came from.
+    #[inline]
+    pub(crate) fn new(data: Vec<u8>, meta_blocks: usize) -> Self {
+        Self { data, meta_blocks }
+    }
+
+    /// Returns the number of meta-blocks decoded, including metadata and empty ones.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::decompress;
+    ///
+    /// // Literals "ab" followed by a copy of 2 bytes at distance 2.
+    /// let stream = *include_bytes!("../tests/fixtures/abab.br");
+    /// let output = decompress(&stream).unwrap();
+    ///
+    /// assert_eq!(output.meta_blocks(), 1);
+    /// assert_eq!(std::str::from_utf8(&output).unwrap(), "abab");
+    /// ```
+    #[inline]
+    pub fn meta_blocks(&self) -> usize {
+        self.meta_blocks
+    }
+
+    /// Consumes the Decompressed and returns the decompressed bytes.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl Deref for Decompressed {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl AsRef<[u8]> for Decompressed {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl From<Vec<u8>> for Decompressed {
+    /// Wraps bytes that did not come from a decoder, with no meta-blocks recorded.
+    #[inline]
+    fn from(data: Vec<u8>) -> Self {
+        Self::new(data, 0)
+    }
+}
+
+impl From<Decompressed> for Vec<u8> {
+    #[inline]
+    fn from(output: Decompressed) -> Self {
+        output.data
+    }
+}
+
+impl PartialEq<[u8]> for Decompressed {
+    #[inline]
+    fn eq(&self, other: &[u8]) -> bool {
+        self.data == other
+    }
+}
+
+impl PartialEq<&[u8]> for Decompressed {
+    #[inline]
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.data == *other
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for Decompressed {
+    #[inline]
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.data == other
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for Decompressed {
+    #[inline]
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        self.data == *other
+    }
+}
+
+impl PartialEq<Vec<u8>> for Decompressed {
+    #[inline]
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        &self.data == other
+    }
+}
+
+/// A stretch of input that was skipped by a lenient decode.
+#[derive(Debug)]
+pub struct DecodeFailure {
+    /// The skipped bits of the compressed input, from the start of the failed meta-block to
+    /// the point where decoding resumed.
+    pub bit_range: Range<usize>,
+    /// The error that started the skipped stretch.
+    pub error: MetaBlockError,
+}
+
+/// Recovered holds the output salvaged by a lenient decode and the stretches it skipped.
+#[derive(Debug)]
+pub struct Recovered {
+    /// The output of every meta-block that decoded successfully, in stream order.
+    pub data: Vec<u8>,
+    /// The stretches of input that could not be decoded, in stream order.
+    pub failures: Vec<DecodeFailure>,
+}
+
+impl Recovered {
+    /// Returns whether the whole stream decoded without skipping anything.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
@@ -0,0 +1,152 @@
+use std::io::{self, Read};
+
+use crate::Decompressor;
+use crate::error::MetaBlockError;
+
+/// BrotliReader decompresses a Brotli stream read from an underlying reader.
+///
+/// The compressed input is read in full on first use, then the decompressed bytes are
+/// served through the `Read` implementation.
+pub struct BrotliReader<R: Read> {
+    /// The reader supplying compressed input.
+    inner: R,
+    /// The decompressor used to decode the stream.
+    decompressor: Decompressor,
+    /// The decompressed output, once decoded.
+    output: Vec<u8>,
+    /// The position of the next byte of output to serve.
+    pos: usize,
+    /// Whether the stream has been decoded.
+    decoded: bool,
+}
+
+impl<R: Read> BrotliReader<R> {
+    /// Creates a new BrotliReader with a default decompressor.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The reader supplying compressed input.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of BrotliReader.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Read;
+    ///
+    /// use brotschneider::BrotliReader;
+    ///
+    /// let stream: &[u8] = include_bytes!("../tests/fixtures/abab.br");
+    /// let mut reader = BrotliReader::new(stream);
+    /// let mut output = Vec::new();
+    /// reader.read_to_end(&mut output).unwrap();
+    ///
+    /// assert_eq!(output, b"abab");
+    /// ```
+    #[inline]
+    pub fn new(inner: R) -> Self {
+        Self::with_decompressor(inner, Decompressor::new())
+    }
+
+    /// Creates a new BrotliReader using the given decompressor.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The reader supplying compressed input.
+    /// * `decompressor` - The configured decompressor to decode with.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of BrotliReader.
+    #[inline]
+    pub fn with_decompressor(inner: R, decompressor: Decompressor) -> Self {
+        Self {
+            inner,
+            decompressor,
+            output: Vec::new(),
+            pos: 0,
+            decoded: false,
+        }
+    }
+
+    /// Read the compressed input and decode it, if not done already.
+    ///
+    /// The `Read` implementation calls this automatically; calling it directly surfaces the
+    /// typed decode error instead of an `io::Error`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the stream was decoded successfully.
+    /// * `Err(MetaBlockError)` if reading or decoding fails.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::Io` if the underlying reader fails.
+    /// * Returns any error produced while decoding the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    ///
+    /// use brotschneider::{BrotliReader, MetaBlockError};
+    ///
+    /// /// A reader that yields one byte and then fails.
+    /// struct Failing(bool);
+    ///
+    /// impl Read for Failing {
+    ///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    ///         if self.0 {
+    ///             return Err(io::Error::new(io::ErrorKind::BrokenPipe, "gone"));
+    ///         }
+    ///
+    ///         self.0 = true;
+    ///         buf[0] = 0b0100_0000;
+    ///         Ok(1)
+    ///     }
+    /// }
+    ///
+    /// let mut reader = BrotliReader::new(Failing(false));
+    /// assert!(matches!(reader.decode(), Err(MetaBlockError::Io(_))));
+    ///
+    /// let mut reader = BrotliReader::new(Failing(false));
+    /// let err = reader.read(&mut [0u8; 8]).unwrap_err();
+    /// assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    /// ```
+    pub fn decode(&mut self) -> Result<(), MetaBlockError> {
+        if self.decoded {
+            return Ok(());
+        }
+
+        let mut input = Vec::new();
+        self.inner.read_to_end(&mut input)?;
+        self.output = self.decompressor.decompress(&input)?.into_vec();
+        self.decoded = true;
+
+        Ok(())
+    }
+
+    /// Consumes the BrotliReader and returns the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for BrotliReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.decode().map_err(|e| match e {
+            MetaBlockError::Io(e) => e,
+            e => io::Error::new(io::ErrorKind::InvalidData, e),
+        })?;
+
+        let remaining = &self.output[self.pos..];
+        let n = remaining.len().min(buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.pos += n;
+
+        Ok(n)
+    }
+}
@@ -0,0 +1,263 @@
+use crate::error::MetaBlockError;
+
+/// RingBuffer holds the most recent bytes of an output stream in a fixed amount of memory.
+///
+/// Once more than `capacity` bytes have been written, the oldest bytes are overwritten, so
+/// backward references can reach at most `capacity` bytes back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingBuffer {
+    /// The stored bytes. Physical order differs from stream order once the buffer wraps.
+    data: Vec<u8>,
+    /// The physical index the next byte is written to.
+    pos: usize,
+    /// The number of bytes written since the buffer was created or cleared, up to capacity.
+    len: usize,
+}
+
+impl RingBuffer {
+    /// Creates a new, empty RingBuffer holding up to `capacity` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of bytes to keep. A capacity of 0 is rounded up to 1.
+    ///
+    /// # Returns
+    ///
+    /// * A new instance of RingBuffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::RingBuffer;
+    ///
+    /// let ring = RingBuffer::new(1 << 16);
+    /// assert_eq!(ring.capacity(), 1 << 16);
+    /// assert!(ring.is_empty());
+    /// ```
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            data: vec![0; capacity.max(1)],
+            pos: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of bytes the buffer can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the number of bytes currently held, which is at most the capacity.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no bytes are held.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discards every byte held, keeping the allocation.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.pos = 0;
+        self.len = 0;
+    }
+
+    /// Appends a byte, overwriting the oldest byte if the buffer is full.
+    #[inline]
+    pub fn push(&mut self, byte: u8) {
+        self.data[self.pos] = byte;
+        self.advance(1);
+    }
+
+    /// Appends bytes, overwriting the oldest bytes if the buffer fills up.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes to append. Only the last `capacity` of them are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use brotschneider::RingBuffer;
+    ///
+    /// let mut ring = RingBuffer::new(4);
+    /// ring.extend_from_slice(b"abc");
+    /// ring.extend_from_slice(b"defghij");
+    /// assert_eq!(ring.to_vec(), b"ghij");
+    /// ```
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        let capacity = self.capacity();
+        let bytes = &bytes[bytes.len().saturating_sub(capacity)..];
+
+        let first = bytes.len().min(capacity - self.pos);
+        self.data[self.pos..self.pos + first].copy_from_slice(&bytes[..first]);
+        self.data[..bytes.len() - first].copy_from_slice(&bytes[first..]);
+        self.advance(bytes.len());
+    }
+
+    /// Returns the byte `distance` bytes back, where 1 is the last byte written.
+    ///
+    /// # Returns
+    ///
+    /// * The byte, or `None` if `distance` is 0 or greater than `len`.
+    #[inline]
+    pub fn get(&self, distance: usize) -> Option<u8> {
+        if distance == 0 || distance > self.len {
+            return None;
+        }
+
+        Some(self.data[self.source_index(distance)])
+    }
+
+    /// Appends `len` bytes copied from `distance` bytes back, as a backward reference.
+    ///
+    /// The copy may overlap the bytes it produces when `len` exceeds `distance`, repeating
+    /// the last `distance` bytes, and either range may wrap around the end of the physical
+    /// buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - How far back the copy starts, from 1 to `len()`.
+    /// * `len` - The number of bytes to copy.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the bytes were copied.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `MetaBlockError::Corrupt` if `distance` is 0 or reaches before the oldest
+    ///   byte held.
+    ///
+    /// # Examples
+    ///
+    /// A source range that wraps the physical end of the buffer:
+    ///
+    /// ```rust
+    /// use brotschneider::RingBuffer;
+    ///
+    /// let mut ring = RingBuffer::new(8);
+    /// // Physically [8, 9, 2, 3, 4, 5, 6, 7], with the next write at index 2.
+    /// ring.extend_from_slice(b"0123456789");
+    ///
+    /// // "789" is read from indexes 7, 0 and 1.
+    /// ring.copy_within(3, 3).unwrap();
+    /// assert_eq!(ring.to_vec(), b"56789789");
+    /// ```
+    ///
+    /// An overlapping copy whose output wraps:
+    ///
+    /// ```rust
+    /// use brotschneider::RingBuffer;
+    ///
+    /// let mut ring = RingBuffer::new(8);
+    /// ring.extend_from_slice(b"abcdef");
+    ///
+    /// // Writes "efefe" to indexes 6, 7, 0, 1 and 2, reading bytes it has just written.
+    /// ring.copy_within(2, 5).unwrap();
+    /// assert_eq!(ring.to_vec(), b"defefefe");
+    /// ```
+    ///
+    /// A distance equal to the capacity, whose source is the byte about to be overwritten:
+    ///
+    /// ```rust
+    /// use brotschneider::{MetaBlockError, RingBuffer};
+    ///
+    /// let mut ring = RingBuffer::new(4);
+    /// ring.extend_from_slice(b"wxyz");
+    /// ring.copy_within(4, 6).unwrap();
+    /// assert_eq!(ring.to_vec(), b"yzwx");
+    ///
+    /// assert!(matches!(ring.copy_within(5, 1), Err(MetaBlockError::Corrupt { .. })));
+    /// assert!(matches!(ring.copy_within(0, 1), Err(MetaBlockError::Corrupt { .. })));
+    ///
+    /// let mut ring = RingBuffer::new(4);
+    /// ring.push(b'a');
+    /// assert!(matches!(ring.copy_within(2, 1), Err(MetaBlockError::Corrupt { .. })));
+    /// ```
+    ///
+    /// Every combination of capacity, fill level, distance and length matches a plain
+    /// vector:
+    ///
+    /// ```rust
+    /// use brotschneider::RingBuffer;
+    ///
+    /// for capacity in 1..=9 {
+    ///     for written in 1..=2 * capacity {
+    ///         for distance in 1..=written.min(capacity) {
+    ///             for len in 0..=2 * capacity + 1 {
+    ///                 let history: Vec<u8> = (0..written as u8).collect();
+    ///                 let mut ring = RingBuffer::new(capacity);
+    ///                 ring.extend_from_slice(&history);
+    ///                 ring.copy_within(distance, len).unwrap();
+    ///
+    ///                 let mut expected = history;
+    ///                 for _ in 0..len {
+    ///                     expected.push(expected[expected.len() - distance]);
+    ///                 }
+    ///                 let kept = expected.len().min(capacity);
+    ///                 assert_eq!(ring.to_vec(), &expected[expected.len() - kept..]);
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn copy_within(&mut self, distance: usize, len: usize) -> Result<(), MetaBlockError> {
+        if distance == 0 || distance > self.len {
+            return Err(MetaBlockError::Corrupt {
+                reason: "invalid distance",
+            });
+        }
+
+        let capacity = self.capacity();
+        let mut remaining = len;
+        while remaining > 0 {
+            let src = self.source_index(distance);
+            // Copying at most `distance` bytes at a time never reads a byte this chunk
+            // writes, and neither range may run past the physical end of the buffer.
+            let chunk = remaining
+                .min(distance)
+                .min(capacity - src)
+                .min(capacity - self.pos);
+
+            self.data.copy_within(src..src + chunk, self.pos);
+            self.advance(chunk);
+            remaining -= chunk;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the bytes held, from oldest to most recent.
+    ///
+    /// # Returns
+    ///
+    /// * A vector of the last `len()` bytes written.
+    pub fn to_vec(&self) -> Vec<u8> {
+        let start = (self.pos + self.capacity() - self.len) % self.capacity();
+        let mut bytes = Vec::with_capacity(self.len);
+        let first = self.len.min(self.capacity() - start);
+        bytes.extend_from_slice(&self.data[start..start + first]);
+        bytes.extend_from_slice(&self.data[..self.len - first]);
+        bytes
+    }
+
+    /// Returns the physical index of the byte `distance` bytes back.
+    #[inline]
+    fn source_index(&self, distance: usize) -> usize {
+        (self.pos + self.capacity() - distance) % self.capacity()
+    }
+
+    /// Moves the write position forward after `n` bytes were written.
+    #[inline]
+    fn advance(&mut self, n: usize) {
+        self.pos = (self.pos + n) % self.capacity();
+        self.len = (self.len + n).min(self.capacity());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::command::{INSERT_COPY_ALPHABET_SIZE, INSERT_LENGTH_BASE, INSERT_LENGTH_EXTRA};
+use crate::error::EncodeError;
+use crate::metablock::{LITERAL_ALPHABET_SIZE, write_uncompressed_block};
+use crate::{BitReader, BitWriter, HuffmanTree, MetaBlockHeader};
+
+/// The size of the distance alphabet with NPOSTFIX = NDIRECT = 0.
+const DISTANCE_ALPHABET_SIZE: usize = 64;
+
+/// Build a stream with no data: the WBITS header and an empty last meta-block.
+///
+/// # Returns
+///
+/// * The complete stream.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{decompress, testing};
+///
+/// assert_eq!(decompress(&testing::empty_stream()).unwrap(), b"");
+/// ```
+pub fn empty_stream() -> Vec<u8> {
+    // WBITS = 16, ISLAST, ISLASTEMPTY.
+    vec![0b0110_0000]
+}
+
+/// Build a stream holding a single metadata meta-block, followed by an empty last
+/// meta-block.
+///
+/// # Arguments
+///
+/// * `metadata` - The bytes of the metadata meta-block, at most 16 MiB.
+///
+/// # Returns
+///
+/// * A Result containing the complete stream.
+///
+/// # Errors
+///
+/// * Returns `EncodeError::BitWriter` if `metadata` is longer than a metadata meta-block
+///   can hold.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{decompress, summarize_meta_blocks, testing};
+///
+/// let stream = testing::metadata_stream(b"comment").unwrap();
+/// assert_eq!(decompress(&stream).unwrap(), b"");
+///
+/// let summaries = summarize_meta_blocks(&stream).unwrap();
+/// assert!(summaries[0].is_metadata);
+/// assert_eq!(summaries[0].length, 7);
+/// ```
+pub fn metadata_stream(metadata: &[u8]) -> Result<Vec<u8>, EncodeError> {
+    let mut writer = BitWriter::new();
+    writer.write_bit(false)?; // WBITS = 16
+
+    MetaBlockHeader {
+        is_last: false,
+        is_last_empty: false,
+        is_metadata: true,
+        is_uncompressed: false,
+        length: u32::try_from(metadata.len()).unwrap_or(u32::MAX),
+    }
+    .write(&mut writer)?;
+
+    writer.flush()?;
+    for &byte in metadata {
+        writer.write_bits(byte as u32, 8)?;
+    }
+    writer.write_bits(0b11, 2)?; // ISLAST, ISLASTEMPTY
+
+    Ok(writer.into_inner())
+}
+
+/// Build a stream storing `data` in uncompressed meta-blocks.
+///
+/// # Arguments
+///
+/// * `data` - The bytes to store. Data longer than 16 MiB spans several meta-blocks.
+///
+/// # Returns
+///
+/// * A Result containing the complete stream.
+///
+/// # Errors
+///
+/// * Returns the same errors as `metablock::write_uncompressed_block`.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{decompress, testing};
+///
+/// for len in [1, 100, 1 << 16, (1 << 16) + 1] {
+///     let data = vec![b'u'; len];
+///     let stream = testing::uncompressed_stream(&data).unwrap();
+///     assert_eq!(decompress(&stream).unwrap(), data);
+/// }
+/// ```
+pub fn uncompressed_stream(data: &[u8]) -> Result<Vec<u8>, EncodeError> {
+    let mut writer = BitWriter::new();
+    writer.write_bit(false)?; // WBITS = 16
+    write_uncompressed_block(&mut writer, data, true)?;
+
+    Ok(writer.into_inner())
+}
+
+/// Build a stream whose last meta-block inserts `literals` with a simple prefix code.
+///
+/// The meta-block has a single command that inserts every literal. Its copy is never
+/// decoded, because the literals complete the meta-block.
+///
+/// # Arguments
+///
+/// * `literals` - The bytes of the meta-block: between 1 byte and 16 MiB, with at most
+///   four distinct values.
+///
+/// # Returns
+///
+/// * A Result containing the complete stream.
+///
+/// # Errors
+///
+/// * Returns `EncodeError::BitWriter` if `literals` is empty, too long, or has more than
+///   four distinct values.
+///
+/// # Examples
+///
+/// ```rust
+/// use brotschneider::{decompress, testing};
+///
+/// for literals in [&b"a"[..], b"abba", b"abcd", b"dcbadcba", &[b'z'; 5000]] {
+///     let stream = testing::literal_stream(literals).unwrap();
+///     assert_eq!(decompress(&stream).unwrap(), literals);
+/// }
+///
+/// assert!(testing::literal_stream(b"").is_err());
+/// assert!(testing::literal_stream(b"abcde").is_err());
+/// ```
+pub fn literal_stream(literals: &[u8]) -> Result<Vec<u8>, EncodeError> {
+    let mut symbols: Vec<u16> = Vec::new();
+    for &literal in literals {
+        if !symbols.contains(&(literal as u16)) {
+            symbols.push(literal as u16);
+        }
+    }
+
+    // The codes of the literals are taken from the tree the decoder builds, so that they
+    // always agree with it.
+    let mut code = BitWriter::new();
+    code.write_simple_code(&symbols, LITERAL_ALPHABET_SIZE)?;
+    let code = code.into_inner();
+    let literal_tree = HuffmanTree::read(&mut BitReader::new(&code), LITERAL_ALPHABET_SIZE)?;
+
+    let insert_len = u32::try_from(literals.len()).unwrap_or(u32::MAX);
+    let insert_code = INSERT_LENGTH_BASE
+        .iter()
+        .rposition(|&base| base <= insert_len)
+        .unwrap_or(0);
+    // Copy code 0 with an explicit distance, in the range holding the insert code.
+    let range = [2, 4, 7][insert_code / 8];
+    let symbol = (range * 64 + (insert_code % 8) * 8) as u16;
+
+    let mut writer = BitWriter::new();
+    writer.write_bit(false)?; // WBITS = 16
+
+    MetaBlockHeader {
+        is_last: true,
+        is_last_empty: false,
+        is_metadata: false,
+        is_uncompressed: false,
+        length: insert_len,
+    }
+    .write(&mut writer)?;
+
+    writer.write_bits(0, 13)?; // single block type and tree, NPOSTFIX = NDIRECT = 0
+    writer.write_simple_code(&symbols, LITERAL_ALPHABET_SIZE)?;
+    writer.write_simple_code(&[symbol], INSERT_COPY_ALPHABET_SIZE)?;
+    writer.write_simple_code(&[0], DISTANCE_ALPHABET_SIZE)?;
+
+    // The only insert-and-copy symbol takes no bits, so the command starts with the extra
+    // bits of its insert length.
+    writer.write_bits(
+        insert_len - INSERT_LENGTH_BASE[insert_code],
+        INSERT_LENGTH_EXTRA[insert_code],
+    )?;
+    for &literal in literals {
+        if let Some((code, len)) = literal_tree.code_for(literal as u16) {
+            writer.write_bits(code, len)?;
+        }
+    }
+
+    Ok(writer.into_inner())
+}
@@ -0,0 +1,59 @@
+//! Decodes every `tests/fixtures/<name>.br` and compares the output byte for byte with
+//! `tests/fixtures/<name>.out`.
+
+use std::fs;
+use std::path::Path;
+
+use brotschneider::{Decompressor, decompress};
+
+fn fixtures() -> Vec<(String, Vec<u8>, Vec<u8>)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut fixtures = Vec::new();
+
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "br") {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let compressed = fs::read(&path).unwrap();
+            let expected = fs::read(path.with_extension("out"))
+                .unwrap_or_else(|e| panic!("missing expected output for {name}: {e}"));
+            fixtures.push((name, compressed, expected));
+        }
+    }
+
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "no fixtures in {}", dir.display());
+    fixtures
+}
+
+#[test]
+fn fixtures_decode_to_expected_output() {
+    for (name, compressed, expected) in fixtures() {
+        let output = decompress(&compressed).unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(output, expected, "{name}: output differs");
+    }
+}
+
+#[test]
+fn fixtures_decode_in_strict_mode() {
+    let mut decompressor = Decompressor::builder().strict(true).build();
+
+    for (name, compressed, expected) in fixtures() {
+        let output = decompressor
+            .decompress(&compressed)
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(output, expected, "{name}: output differs");
+    }
+}
+
+#[test]
+fn truncated_fixtures_fail() {
+    for (name, compressed, _) in fixtures() {
+        for len in 0..compressed.len() {
+            assert!(
+                decompress(&compressed[..len]).is_err(),
+                "{name}: decoded when truncated to {len} bytes"
+            );
+        }
+    }
+}